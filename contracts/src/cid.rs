@@ -0,0 +1,113 @@
+use crate::error::ContractError;
+
+pub const MAX_CID_LENGTH: usize = 256;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+// sha2-256 multihash header used by every CIDv0
+const CID_V0_PREFIX: [u8; 2] = [0x12, 0x20];
+
+/// Checks that `cid` is a syntactically valid IPFS content identifier.
+///
+/// CIDv0 is a base58btc sha2-256 multihash ("Qm..."). CIDv1 is accepted in
+/// base32 ("b"/"B"), base58btc ("z") and base16 ("f") multibase, and must carry
+/// version 1, a codec and a multihash whose digest length matches its header.
+pub fn validate_cid(cid: &str) -> Result<(), ContractError> {
+    let invalid = || ContractError::InvalidCid { cid: cid.to_string() };
+    if cid.len() > MAX_CID_LENGTH {
+        return Err(invalid());
+    }
+
+    if cid.len() == 46 && cid.starts_with("Qm") {
+        let bytes = decode_base58(cid).ok_or_else(invalid)?;
+        if bytes.len() != 34 || bytes[..2] != CID_V0_PREFIX {
+            return Err(invalid());
+        }
+        return Ok(());
+    }
+
+    let mut chars = cid.chars();
+    let bytes = match chars.next() {
+        Some('b') => decode_base32(chars.as_str()),
+        Some('B') => decode_base32(&chars.as_str().to_ascii_lowercase()),
+        Some('z') => decode_base58(chars.as_str()),
+        Some('f') => hex::decode(chars.as_str()).ok(),
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+
+    let mut rest = bytes.as_slice();
+    let version = read_varint(&mut rest).ok_or_else(invalid)?;
+    let _codec = read_varint(&mut rest).ok_or_else(invalid)?;
+    let _hash_code = read_varint(&mut rest).ok_or_else(invalid)?;
+    let digest_length = read_varint(&mut rest).ok_or_else(invalid)?;
+    if version != 1 || digest_length == 0 || rest.len() as u64 != digest_length {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+// Unsigned LEB128 as used by multiformats, at most 9 bytes.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for i in 0..9 {
+        let (byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn decode_base58(input: &str) -> Option<Vec<u8>> {
+    if input.is_empty() {
+        return None;
+    }
+    // Little-endian big number accumulator
+    let mut bytes: Vec<u8> = vec![];
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+    // Each leading '1' encodes a leading zero byte
+    let leading_zeros = input.bytes().take_while(|&c| c == b'1').count();
+    bytes.resize(bytes.len() + leading_zeros, 0);
+    bytes.reverse();
+    Some(bytes)
+}
+
+// RFC 4648 lowercase alphabet without padding.
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    if input.is_empty() {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in input.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // A canonical encoding ends on fewer than 5 spare bits, all of them zero
+    if bits >= 5 || buffer != 0 {
+        return None;
+    }
+    Some(bytes)
+}
@@ -1,40 +1,161 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use std::str::FromStr;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Timestamp, Env, MessageInfo, Response, StdResult, Uint128, Addr};
+use cosmwasm_std::{coins, from_binary, to_binary, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, Isqrt, DepsMut, Event, Timestamp, Env, MessageInfo, Reply, Response, StdResult, SubMsg, Uint128, Addr, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
+use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use cw_utils::{parse_reply_instantiate_data, Expiration};
 use cw_storage_plus::Bound;
+use crate::cid::validate_cid;
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg,QueryMsg,ConfigResponse, ClaimResponse, OrganizationResponse, TotalCarbonCreditsResponse, ClaimsResponse, OrganizationListItem,OrganizationsResponse};
-use crate::state::{Config, CONFIG, CLAIMS, VOTES, CLAIM_COUNTER, LendRequestResponse, LEND_REQUEST_COUNTER,LEND_REQUESTS, ORGANIZATIONS, Claim, ClaimStatus,LentStatus,OrganizationInfo, VoteOption, LendRequest};
-use zero_knowledge_proofs::eligibility_proof;
+use crate::geometry::{covering_tiles, polygons_overlap, validate_polygon, Polygon};
+use crate::msg::{ExecuteMsg, InstantiateMsg, ReceiveMsg,QueryMsg,ConfigResponse, ClaimResponse, OrganizationResponse, TotalCarbonCreditsResponse, ClaimsResponse, OrganizationListItem,OrganizationsResponse, VerifierResponse, VerifiersResponse, VotingRecordResponse, DisputeResponse, ConflictingClaimsResponse, MethodologyResponse, MethodologiesResponse, ClaimCapResponse, RetirementsResponse, CreditBatchesResponse, AllowanceResponse, AllowancesResponse, OrderBookResponse, PoolResponse, PoolsResponse, PoolSharesResponse};
+use crate::state::{Config, CONFIG, CLAIMS, VOTES, CLAIM_COUNTER, LendRequestResponse, LEND_REQUEST_COUNTER,LEND_REQUESTS, DEFAULTED_LOANS, ORGANIZATIONS, CollateralConfig, Pool, POOLS, POOL_SHARES, POOL_COUNTER, VERIFYING_KEY, ELIGIBILITY_COMMITMENTS, EligibilityCommitment, Claim, ClaimStatus,LentStatus,OrganizationInfo, Vote, VoteOption, VotingPowerModel, LendRequest, VerifierInfo, VERIFIERS, VERIFIER_COUNT, VoteRecord, VOTE_RECORDS};
+use zero_knowledge_proofs::field::decode_u64;
+use zero_knowledge_proofs::{check_verifying_key, eligibility_public_inputs, is_field_element, verify_proof};
 use std::convert::TryFrom;
 use cosmwasm_std::StdError;
 use cw_storage_plus::Map;
 use hex;
 use cosmwasm_std::Order;
 use crate::state::UserLendRequestsResponse;
+use crate::state::{VoteCommitment, COMMITMENTS, Dispute, DisputeStatus, DISPUTES, CLAIM_TILES, Methodology, METHODOLOGIES, CreditBatch, CREDIT_BATCHES, Allowance, ALLOWANCES, RetirementCertificate, RETIREMENTS, ORGANIZATION_RETIREMENTS, RETIREMENT_COUNTER, MarketOrder, OrderSide, OrderStatus, MARKET_ORDERS, ASKS, BIDS, ORDER_COUNTER};
+use sha2::{Digest, Sha256};
 
 
+pub const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    validate_ratios(&[msg.quorum, msg.threshold, msg.bond_slash, msg.dispute_quorum])?;
     let config = Config {
         owner: info.sender.clone(),
         voting_period: msg.voting_period,
         total_carbon_credits: Uint128::zero(),
+        min_voters: msg.min_voters,
+        quorum: msg.quorum,
+        threshold: msg.threshold,
+        voting_power: msg.voting_power,
+        verifier_min_reputation: msg.verifier_min_reputation,
+        verifier_min_bond: msg.verifier_min_bond,
+        vote_reward: msg.vote_reward,
+        vote_slash: msg.vote_slash,
+        bond_slash: msg.bond_slash,
+        commit_reveal: msg.commit_reveal,
+        reveal_period: msg.reveal_period,
+        unrevealed_slash: msg.unrevealed_slash,
+        challenge_period: msg.challenge_period,
+        dispute_bond: msg.dispute_bond,
+        dispute_min_voters: msg.dispute_min_voters,
+        dispute_quorum: msg.dispute_quorum,
+        max_claim_period: msg.max_claim_period,
+        credit_token: None,
+        max_loan_term: msg.max_loan_term,
+        loan_grace_period: msg.loan_grace_period,
+        default_penalty: msg.default_penalty,
+        seize_defaulted_issuance: msg.seize_defaulted_issuance,
+        min_eligibility_score: msg.min_eligibility_score,
+        collateral: msg.collateral,
+        quote_denoms: msg.quote_denoms,
     };
+    validate_dispute_round(&config)?;
     CONFIG.save(deps.storage, &config)?;
     CLAIM_COUNTER.save(deps.storage, &0u64)?;
-    LEND_REQUEST_COUNTER.save(deps.storage, &0u64);
+    VERIFIER_COUNT.save(deps.storage, &0u64)?;
+    RETIREMENT_COUNTER.save(deps.storage, &0u64)?;
+    ORDER_COUNTER.save(deps.storage, &0u64)?;
+    POOL_COUNTER.save(deps.storage, &0u64)?;
+    for methodology in &msg.methodologies {
+        METHODOLOGIES.save(deps.storage, &methodology.id, methodology)?;
+    }
+    LEND_REQUEST_COUNTER.save(deps.storage, &0u64)?;
+
+    // The token address is recorded in `reply` once the CW20 is instantiated
+    let mut messages = vec![];
+    if let Some(token) = msg.credit_token {
+        let token_msg = Cw20InstantiateMsg {
+            name: token.name,
+            symbol: token.symbol,
+            decimals: token.decimals,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: env.contract.address.to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        };
+        messages.push(SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: Some(info.sender.to_string()),
+                code_id: token.code_id,
+                msg: to_binary(&token_msg)?,
+                funds: vec![],
+                label: "carbon credits".to_string(),
+            },
+            INSTANTIATE_TOKEN_REPLY_ID,
+        ));
+    }
 
     Ok(Response::new()
+        .add_submessages(messages)
         .add_attribute("method", "instantiate")
         .add_attribute("owner", info.sender)
-        .add_attribute("voting_period", msg.voting_period.to_string()))
+        .add_attribute("voting_period", msg.voting_period.to_string())
+        .add_attribute("min_voters", msg.min_voters.to_string())
+        .add_attribute("quorum", msg.quorum.to_string())
+        .add_attribute("threshold", msg.threshold.to_string())
+        .add_attribute("voting_power", format!("{:?}", msg.voting_power)))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != INSTANTIATE_TOKEN_REPLY_ID {
+        return Err(ContractError::UnknownReplyId { id: msg.id });
+    }
+    let res = parse_reply_instantiate_data(msg)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let token = deps.api.addr_validate(&res.contract_address)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.credit_token = Some(token.clone());
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate_credit_token")
+        .add_attribute("credit_token", token))
+}
+
+/// Builds a CW20 message on the credit token, if credits are token backed.
+/// This contract holds every token behind the balances it records: they come in
+/// through `Receive`, leave through `WithdrawCredits` and otherwise only move here.
+fn credit_token_msg(config: &Config, msg: Cw20ExecuteMsg) -> StdResult<Option<WasmMsg>> {
+    match &config.credit_token {
+        Some(token) => Ok(Some(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        })),
+        None => Ok(None),
+    }
+}
+
+fn validate_ratios(ratios: &[Decimal]) -> Result<(), ContractError> {
+    if ratios.iter().any(|ratio| *ratio > Decimal::one()) {
+        return Err(ContractError::InvalidVotingConfig {});
+    }
+    Ok(())
+}
+
+/// A dispute round overrides a regular vote, so it must be at least as hard to reach.
+fn validate_dispute_round(config: &Config) -> Result<(), ContractError> {
+    if config.dispute_min_voters < config.min_voters || config.dispute_quorum < config.quorum {
+        return Err(ContractError::InvalidDisputeConfig {});
+    }
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -45,36 +166,140 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::CreateClaim { longitudes, latitudes, time_started, time_ended, demanded_tokens, ipfs_hashes } => {
-            execute_create_claim(deps, env, info, longitudes, latitudes, time_started, time_ended, demanded_tokens, ipfs_hashes)
+        ExecuteMsg::CreateClaim { longitudes, latitudes, time_started, time_ended, demanded_tokens, ipfs_hashes, methodology } => {
+            execute_create_claim(deps, env, info, longitudes, latitudes, time_started, time_ended, demanded_tokens, ipfs_hashes, methodology)
         },
-        ExecuteMsg::CreateLendToken {lender, amount} => {
-            execute_request_tokens(deps, env, info,lender, amount)
+        ExecuteMsg::CreateLendToken {lender, amount, interest_rate, term, proof} => {
+            execute_request_tokens(deps, env, info,lender, amount, interest_rate, term, proof)
         },
         ExecuteMsg::CastVote { claim_id, vote } => {
             execute_cast_vote(deps, env, info, claim_id, vote)
         },
+        ExecuteMsg::CommitVote { claim_id, commitment } => {
+            execute_commit_vote(deps, env, info, claim_id, commitment)
+        },
+        ExecuteMsg::RevealVote { claim_id, vote, salt } => {
+            execute_reveal_vote(deps, env, info, claim_id, vote, salt)
+        },
         ExecuteMsg::FinalizeVoting { claim_id } => {
             execute_finalize_voting(deps, env, info, claim_id)
         },
-        ExecuteMsg::LendTokens { lend_request_id, response } => {
-            execute_lend_tokens(deps, env, info, lend_request_id, response)
+        ExecuteMsg::DisputeClaim { claim_id } => {
+            execute_dispute_claim(deps, env, info, claim_id)
+        },
+        ExecuteMsg::LendTokens { lend_request_id, response, interest_rate } => {
+            execute_lend_tokens(deps, env, info, lend_request_id, response, interest_rate)
+        },
+        ExecuteMsg::CancelLendRequest { lend_request_id } => {
+            execute_cancel_lend_request(deps, env, info, lend_request_id)
+        },
+        ExecuteMsg::RepayTokens { loan_id, amount } => {
+            execute_repay_tokens(deps, env, info, loan_id, amount)
         },
-        ExecuteMsg::RepayTokens { lender, amount } => {
-            execute_repay_tokens(deps, env, info, lender, amount)
+        ExecuteMsg::VerifyProof { lend_request_id, proof, public_inputs } => {
+            execute_verify_proof(deps, env, info, lend_request_id, proof, public_inputs)
         },
-        ExecuteMsg::VerifyEligibility { borrower, amount, lender} => {
-            execute_verify_eligibility(deps, env, info, borrower,lender,amount)
+        ExecuteMsg::UpdateVerifyingKey { verifying_key } => {
+            execute_update_verifying_key(deps, env, info, verifying_key)
+        },
+        ExecuteMsg::CommitEligibility { commitment } => {
+            execute_commit_eligibility(deps, env, info, commitment)
+        },
+        ExecuteMsg::AttestEligibility { borrower, commitment } => {
+            execute_attest_eligibility(deps, env, info, borrower, commitment)
         },
         ExecuteMsg::UpdateOrganizationName { name } => {
             execute_update_organization_name(deps, env, info, name)
         },
         ExecuteMsg::AddOrganizationEmission { emissions } => {
             add_organization_emission(deps, env, info, emissions) // Add this handler
+        },
+        ExecuteMsg::UpdateVotingConfig { voting_period, min_voters, quorum, threshold, voting_power, verifier_min_reputation, verifier_min_bond, commit_reveal, reveal_period } => {
+            execute_update_voting_config(deps, env, info, voting_period, min_voters, quorum, threshold, voting_power, verifier_min_reputation, verifier_min_bond, commit_reveal, reveal_period)
+        },
+        ExecuteMsg::AddVerifier { address } => {
+            execute_add_verifier(deps, env, info, address)
+        },
+        ExecuteMsg::RemoveVerifier { address } => {
+            execute_remove_verifier(deps, env, info, address)
+        },
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, env, info, wrapper),
+        ExecuteMsg::WithdrawCredits { amount } => {
+            execute_withdraw_credits(deps, env, info, amount)
+        }
+        ExecuteMsg::TransferCredits { recipient, amount } => {
+            execute_transfer_credits(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::ApproveCredits { spender, amount, expires } => {
+            execute_approve_credits(deps, env, info, spender, amount, expires)
+        }
+        ExecuteMsg::RevokeCredits { spender } => {
+            execute_revoke_credits(deps, env, info, spender)
+        }
+        ExecuteMsg::TransferCreditsFrom { owner, recipient, amount } => {
+            execute_transfer_credits_from(deps, env, info, owner, recipient, amount)
+        }
+        ExecuteMsg::RetireCredits { amount, beneficiary, reason } => {
+            execute_retire_credits(deps, env, info, amount, beneficiary, reason)
+        }
+        ExecuteMsg::PlaceAsk { denom, price, amount } => {
+            execute_place_ask(deps, env, info, denom, price, amount)
+        },
+        ExecuteMsg::PlaceBid { price, amount } => {
+            execute_place_bid(deps, env, info, price, amount)
+        },
+        ExecuteMsg::CancelOrder { order_id } => {
+            execute_cancel_order(deps, env, info, order_id)
+        },
+        ExecuteMsg::BondCredits { amount } => {
+            execute_bond_credits(deps, env, info, amount)
+        },
+        ExecuteMsg::UnbondCredits { amount } => {
+            execute_unbond_credits(deps, env, info, amount)
+        },
+        ExecuteMsg::UpdateVoteIncentives { vote_reward, vote_slash, bond_slash, unrevealed_slash } => {
+            execute_update_vote_incentives(deps, env, info, vote_reward, vote_slash, bond_slash, unrevealed_slash)
+        },
+        ExecuteMsg::UpdateDisputeConfig { challenge_period, dispute_bond, dispute_min_voters, dispute_quorum } => {
+            execute_update_dispute_config(deps, env, info, challenge_period, dispute_bond, dispute_min_voters, dispute_quorum)
+        }
+        ExecuteMsg::UpdateClaimConfig { max_claim_period } => {
+            execute_update_claim_config(deps, env, info, max_claim_period)
+        }
+        ExecuteMsg::UpdateLoanConfig { max_loan_term, loan_grace_period, default_penalty, seize_defaulted_issuance, min_eligibility_score } => {
+            execute_update_loan_config(deps, env, info, max_loan_term, loan_grace_period, default_penalty, seize_defaulted_issuance, min_eligibility_score)
+        },
+        ExecuteMsg::MarkDefault { loan_id } => {
+            execute_mark_default(deps, env, info, loan_id)
+        },
+        ExecuteMsg::UpdateCollateralConfig { collateral } => {
+            execute_update_collateral_config(deps, env, info, collateral)
+        },
+        ExecuteMsg::UpdateMarketConfig { quote_denoms } => {
+            execute_update_market_config(deps, env, info, quote_denoms)
+        },
+        ExecuteMsg::CreatePool { min_eligibility_score, base_rate, rate_slope, term } => {
+            execute_create_pool(deps, env, info, min_eligibility_score, base_rate, rate_slope, term)
+        },
+        ExecuteMsg::DepositToPool { pool_id, amount } => {
+            execute_deposit_to_pool(deps, env, info, pool_id, amount)
+        },
+        ExecuteMsg::WithdrawFromPool { pool_id, shares } => {
+            execute_withdraw_from_pool(deps, env, info, pool_id, shares)
+        },
+        ExecuteMsg::BorrowFromPool { pool_id, amount, proof } => {
+            execute_borrow_from_pool(deps, env, info, pool_id, amount, proof)
+        },
+        ExecuteMsg::SetMethodology { methodology } => {
+            execute_set_methodology(deps, env, info, methodology)
+        }
+        ExecuteMsg::RemoveMethodology { id } => {
+            execute_remove_methodology(deps, env, info, id)
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_create_claim(
     deps: DepsMut,
     env: Env,
@@ -85,9 +310,41 @@ pub fn execute_create_claim(
     time_ended: u64,
     demanded_tokens: Uint128,
     ipfs_hashes: Vec<String>,
+    methodology: String,
 ) -> Result<Response, ContractError> {
-    let mut claim_counter = CLAIM_COUNTER.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
+    if time_started >= time_ended {
+        return Err(ContractError::InvalidClaimPeriod {});
+    }
+    if time_ended > env.block.time.seconds() {
+        return Err(ContractError::ClaimPeriodNotEnded {});
+    }
+    if time_ended - time_started > config.max_claim_period {
+        return Err(ContractError::ClaimPeriodTooLong { max: config.max_claim_period });
+    }
+    if ipfs_hashes.is_empty() {
+        return Err(ContractError::MissingEvidence {});
+    }
+    for ipfs_hash in &ipfs_hashes {
+        validate_cid(ipfs_hash)?;
+    }
+    let polygon = validate_polygon(&longitudes, &latitudes)?;
+    let rate = METHODOLOGIES.may_load(deps.storage, &methodology)?
+        .ok_or(ContractError::MethodologyNotFound {})?;
+    let max_tokens = claim_cap(&rate, polygon.area, time_ended - time_started);
+    if demanded_tokens > max_tokens {
+        return Err(ContractError::DemandExceedsCap { max: max_tokens });
+    }
+    if let Some(claim_id) = conflicting_claims(deps.as_ref(), &polygon, time_started, time_ended)?.first() {
+        return Err(ContractError::OverlappingClaim { claim_id: *claim_id });
+    }
+    let mut claim_counter = CLAIM_COUNTER.load(deps.storage)?;
+    let voting_end_time = env.block.time.seconds() + config.voting_period;
+    let reveal_end_time = if config.commit_reveal {
+        voting_end_time + config.reveal_period
+    } else {
+        voting_end_time
+    };
     let claim = Claim {
         id: claim_counter,
         organization: info.sender.clone(),
@@ -97,12 +354,18 @@ pub fn execute_create_claim(
         time_ended,
         demanded_tokens,
         ipfs_hashes,
+        methodology,
+        polygon,
         status: ClaimStatus::Active,
-        voting_end_time: env.block.time.seconds() + config.voting_period,
+        voting_end_time,
+        commit_reveal: config.commit_reveal,
+        reveal_end_time,
         yes_votes: Uint128::zero(),
         no_votes: Uint128::zero(),
+        finalized_at: None,
     };
     CLAIMS.save(deps.storage, claim_counter, &claim)?;
+    index_claim(deps.storage, &claim)?;
     claim_counter += 1;
     CLAIM_COUNTER.save(deps.storage, &claim_counter)?;
     
@@ -126,24 +389,181 @@ pub fn execute_cast_vote(
     if env.block.time.seconds() > claim.voting_end_time {
         return Err(ContractError::VotingEnded {});
     }
+    if claim.commit_reveal {
+        return Err(ContractError::CommitRevealRequired {});
+    }
 
     if VOTES.has(deps.storage, (claim_id, &info.sender)) {
         return Err(ContractError::AlreadyVoted {});
     }
-    VOTES.save(deps.storage, (claim_id, &info.sender), &vote)?;
+    let weight = eligible_voting_power(deps.as_ref(), &claim, &info.sender)?;
+    let ballot = Vote {
+        voter: info.sender.clone(),
+        vote: vote.clone(),
+        weight,
+        timestamp: env.block.time.seconds(),
+    };
+    VOTES.save(deps.storage, (claim_id, &info.sender), &ballot)?;
+    track_pending_vote(deps.storage, &info.sender, true)?;
 
 
     match vote {
-        VoteOption::Yes => claim.yes_votes += Uint128::new(1),
-        VoteOption::No => claim.no_votes += Uint128::new(1),
+        VoteOption::Yes => claim.yes_votes += weight,
+        VoteOption::No => claim.no_votes += weight,
     }
     CLAIMS.save(deps.storage, claim_id, &claim)?;
     Ok(Response::new()
         .add_attribute("method", "cast_vote")
         .add_attribute("claim_id", claim_id.to_string())
+        .add_attribute("voter", info.sender)
+        .add_attribute("weight", weight))
+}
+
+pub fn execute_commit_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    claim_id: u64,
+    commitment: String,
+) -> Result<Response, ContractError> {
+    let claim = CLAIMS.load(deps.storage, claim_id)?;
+    if !claim.commit_reveal {
+        return Err(ContractError::CommitRevealDisabled {});
+    }
+    if env.block.time.seconds() > claim.voting_end_time {
+        return Err(ContractError::VotingEnded {});
+    }
+    if COMMITMENTS.has(deps.storage, (claim_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    let weight = eligible_voting_power(deps.as_ref(), &claim, &info.sender)?;
+    let vote_commitment = VoteCommitment {
+        commitment: commitment.to_lowercase(),
+        weight,
+        timestamp: env.block.time.seconds(),
+    };
+    COMMITMENTS.save(deps.storage, (claim_id, &info.sender), &vote_commitment)?;
+    track_pending_vote(deps.storage, &info.sender, true)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "commit_vote")
+        .add_attribute("claim_id", claim_id.to_string())
         .add_attribute("voter", info.sender))
 }
 
+pub fn execute_reveal_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    claim_id: u64,
+    vote: VoteOption,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut claim = CLAIMS.load(deps.storage, claim_id)?;
+    if !claim.commit_reveal {
+        return Err(ContractError::CommitRevealDisabled {});
+    }
+    if env.block.time.seconds() <= claim.voting_end_time {
+        return Err(ContractError::RevealNotStarted {});
+    }
+    if env.block.time.seconds() > claim.reveal_end_time {
+        return Err(ContractError::RevealEnded {});
+    }
+    let vote_commitment = COMMITMENTS.may_load(deps.storage, (claim_id, &info.sender))?
+        .ok_or(ContractError::CommitmentNotFound {})?;
+    if vote_commitment.commitment != vote_commitment_hash(&info.sender, claim_id, &vote, &salt) {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    // The weight snapshotted at commit time is the one that counts
+    let ballot = Vote {
+        voter: info.sender.clone(),
+        vote: vote.clone(),
+        weight: vote_commitment.weight,
+        timestamp: env.block.time.seconds(),
+    };
+    VOTES.save(deps.storage, (claim_id, &info.sender), &ballot)?;
+    COMMITMENTS.remove(deps.storage, (claim_id, &info.sender));
+
+    match vote {
+        VoteOption::Yes => claim.yes_votes += ballot.weight,
+        VoteOption::No => claim.no_votes += ballot.weight,
+    }
+    CLAIMS.save(deps.storage, claim_id, &claim)?;
+    Ok(Response::new()
+        .add_attribute("method", "reveal_vote")
+        .add_attribute("claim_id", claim_id.to_string())
+        .add_attribute("voter", info.sender)
+        .add_attribute("weight", ballot.weight))
+}
+
+/// Commitment a voter has to submit with `CommitVote`. Binding the voter and
+/// claim into the preimage stops commitments from being copied.
+pub fn vote_commitment_hash(voter: &Addr, claim_id: u64, vote: &VoteOption, salt: &str) -> String {
+    let vote = match vote {
+        VoteOption::Yes => "yes",
+        VoteOption::No => "no",
+    };
+    let preimage = format!("{}:{}:{}:{}", voter, claim_id, vote, salt);
+    hex::encode(Sha256::digest(preimage.as_bytes()))
+}
+
+fn eligible_voting_power(deps: Deps, claim: &Claim, voter: &Addr) -> Result<Uint128, ContractError> {
+    if claim.organization == *voter {
+        return Err(ContractError::SelfVote {});
+    }
+    if let Some(dispute) = DISPUTES.may_load(deps.storage, claim.id)? {
+        if dispute.status == DisputeStatus::Open && dispute.disputer == *voter {
+            return Err(ContractError::SelfVote {});
+        }
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let verifier = VERIFIERS.may_load(deps.storage, voter)?
+        .ok_or(ContractError::NotVerifier {})?;
+    if verifier.bonded_credits < config.verifier_min_bond {
+        return Err(ContractError::InsufficientBond {});
+    }
+    let reputation = ORGANIZATIONS.may_load(deps.storage, voter)?
+        .map(|org_info| org_info.reputation_score)
+        .unwrap_or_default();
+    if reputation < config.verifier_min_reputation {
+        return Err(ContractError::NotEnoughReputation {});
+    }
+
+    // The weight is fixed at voting time so later bond or reputation
+    // changes cannot alter the tally
+    let weight = voting_power(config.voting_power, reputation, verifier.bonded_credits);
+    if weight.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+    Ok(weight)
+}
+
+/// Counts a vote or commit of a verifier while its claim is unfinalized, keeping
+/// the bond that backs it from being unbonded before the vote is settled.
+fn track_pending_vote(storage: &mut dyn cosmwasm_std::Storage, voter: &Addr, pending: bool) -> StdResult<()> {
+    // Verifiers removed in the meantime no longer have a bond to lock
+    if let Some(mut verifier) = VERIFIERS.may_load(storage, voter)? {
+        if pending {
+            verifier.pending_votes += 1;
+        } else {
+            verifier.pending_votes = verifier.pending_votes.saturating_sub(1);
+        }
+        VERIFIERS.save(storage, voter, &verifier)?;
+    }
+    Ok(())
+}
+
+fn voting_power(model: VotingPowerModel, reputation: Uint128, bonded_credits: Uint128) -> Uint128 {
+    match model {
+        VotingPowerModel::OneAddressOneVote => Uint128::new(1),
+        VotingPowerModel::Reputation => reputation,
+        VotingPowerModel::Credits => bonded_credits,
+        VotingPowerModel::Quadratic => bonded_credits.isqrt(),
+    }
+}
+
 pub fn execute_finalize_voting(
     deps: DepsMut,
     env: Env,
@@ -151,14 +571,146 @@ pub fn execute_finalize_voting(
     claim_id: u64,
 ) -> Result<Response, ContractError> {
     let mut claim = CLAIMS.load(deps.storage, claim_id)?;
-    if env.block.time.seconds() <= claim.voting_end_time {
+    if claim.status != ClaimStatus::Active && claim.status != ClaimStatus::Disputed {
+        return Err(ContractError::ClaimNotActive {});
+    }
+    if env.block.time.seconds() <= claim.reveal_end_time {
         return Err(ContractError::VotingNotEnded {});
     }
-    let approved = claim.yes_votes >= claim.no_votes;
-    claim.status = if approved { ClaimStatus::Approved } else { ClaimStatus::Rejected };
     let mut config = CONFIG.load(deps.storage)?;
+
+    // Commits that were never revealed count as abstentions
+    let unrevealed: Vec<Addr> = COMMITMENTS
+        .prefix(claim_id)
+        .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut events = vec![];
+    for voter_addr in unrevealed {
+        COMMITMENTS.remove(deps.storage, (claim_id, &voter_addr));
+        track_pending_vote(deps.storage, &voter_addr, false)?;
+        let mut reputation_lost = Uint128::zero();
+        if let Some(mut org_info) = ORGANIZATIONS.may_load(deps.storage, &voter_addr)? {
+            reputation_lost = config.unrevealed_slash.min(org_info.reputation_score);
+            org_info.reputation_score -= reputation_lost;
+            ORGANIZATIONS.save(deps.storage, &voter_addr, &org_info)?;
+        }
+        events.push(Event::new("unrevealed_commit")
+            .add_attribute("claim_id", claim_id.to_string())
+            .add_attribute("voter", voter_addr)
+            .add_attribute("reputation_lost", reputation_lost));
+    }
+
+    let voters: Vec<(u64, Addr)> = VOTES
+        .prefix(claim_id)
+        .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|key| key.map(|addr| (claim_id, addr)))
+        .collect::<Result<Vec<_>, _>>()?;
+    // The round is over whether or not quorum is reached, which unlocks the voters' bonds
+    for (_, voter_addr) in &voters {
+        track_pending_vote(deps.storage, voter_addr, false)?;
+    }
+
+    // A dispute round re-runs the vote under a stricter quorum
+    let dispute = if claim.status == ClaimStatus::Disputed {
+        Some(DISPUTES.load(deps.storage, claim_id)?)
+    } else {
+        None
+    };
+    let (min_voters, quorum) = match dispute {
+        Some(_) => (config.dispute_min_voters, config.dispute_quorum),
+        None => (config.min_voters, config.quorum),
+    };
+    claim.finalized_at = Some(env.block.time.seconds());
+    let mut messages = vec![];
+
+    // Turnout is measured against the verifier registry, the only addresses able to vote
+    let registered_verifiers = VERIFIER_COUNT.load(deps.storage)?;
+    let turnout = Uint128::from(voters.len() as u128);
+    let quorum_reached = voters.len() as u64 >= min_voters
+        && Decimal::from_ratio(turnout, 1u128) >= Decimal::from_ratio(registered_verifiers, 1u64) * quorum;
+    if !quorum_reached {
+        match dispute {
+            // The original decision stands and the disputer gets the bond back
+            Some(mut dispute) => {
+                refund_dispute_bond(deps.storage, &dispute)?;
+                claim.status = dispute.original_status.clone();
+                dispute.status = DisputeStatus::FailedQuorum;
+                DISPUTES.save(deps.storage, claim_id, &dispute)?;
+            }
+            None => claim.status = ClaimStatus::FailedQuorum,
+        }
+        if claim.status != ClaimStatus::Approved {
+            unindex_claim(deps.storage, &claim)?;
+        }
+        CLAIMS.save(deps.storage, claim_id, &claim)?;
+        return Ok(Response::new()
+            .add_messages(messages)
+            .add_events(events)
+            .add_attribute("method", "finalize_voting")
+            .add_attribute("claim_id", claim_id.to_string())
+            .add_attribute("status", format!("{:?}", claim.status))
+            .add_attribute("turnout", turnout.to_string()));
+    }
+
+    let total_votes = claim.yes_votes + claim.no_votes;
+    let approved = !total_votes.is_zero()
+        && Decimal::from_ratio(claim.yes_votes, total_votes) >= config.threshold;
+    claim.status = if approved { ClaimStatus::Approved } else { ClaimStatus::Rejected };
+
+    // Credits were already minted if the disputed decision was an approval
+    let previously_approved = matches!(&dispute, Some(dispute) if dispute.original_status == ClaimStatus::Approved);
+    if let Some(mut dispute) = dispute {
+        if approved != previously_approved {
+            // The first round backed the wrong outcome, so its voters are judged again below
+            messages.extend(settle_first_round(deps.storage, &mut config, &env.contract.address, claim_id, &dispute.original_voters)?);
+            refund_dispute_bond(deps.storage, &dispute)?;
+            dispute.status = DisputeStatus::Overturned;
+        } else {
+            config.total_carbon_credits = config.total_carbon_credits.saturating_sub(dispute.bond);
+            dispute.status = DisputeStatus::Upheld;
+            if !dispute.bond.is_zero() {
+                messages.extend(credit_token_msg(&config, Cw20ExecuteMsg::Burn { amount: dispute.bond })?);
+            }
+        }
+        events.push(Event::new("dispute_resolved")
+            .add_attribute("claim_id", claim_id.to_string())
+            .add_attribute("disputer", dispute.disputer.clone())
+            .add_attribute("outcome", format!("{:?}", dispute.status)));
+        DISPUTES.save(deps.storage, claim_id, &dispute)?;
+    }
+
+    if previously_approved && !approved {
+        // Claw back the minted credits, limited to what the claimant still holds
+        let mut org_info = ORGANIZATIONS.load(deps.storage, &claim.organization)?;
+        let burned = claim.demanded_tokens.min(org_info.carbon_credits);
+        org_info.carbon_credits -= burned;
+        ORGANIZATIONS.save(deps.storage, &claim.organization, &org_info)?;
+        // Burn from the claim's own batch before any other
+        let mut remaining = burned;
+        if let Some(mut batch) = CREDIT_BATCHES.may_load(deps.storage, (&claim.organization, claim_id))? {
+            let taken = batch.amount.min(remaining);
+            batch.amount -= taken;
+            remaining -= taken;
+            if batch.amount.is_zero() {
+                CREDIT_BATCHES.remove(deps.storage, (&claim.organization, claim_id));
+            } else {
+                CREDIT_BATCHES.save(deps.storage, (&claim.organization, claim_id), &batch)?;
+            }
+        }
+        take_credit_batches(deps.storage, &claim.organization, remaining)?;
+        config.total_carbon_credits = config.total_carbon_credits.saturating_sub(burned);
+        // The clawed back credits are held here, so the contract burns from its own balance
+        if !burned.is_zero() {
+            messages.extend(credit_token_msg(&config, Cw20ExecuteMsg::Burn { amount: burned })?);
+        }
+        events.push(Event::new("credits_burned")
+            .add_attribute("claim_id", claim_id.to_string())
+            .add_attribute("organization", claim.organization.clone())
+            .add_attribute("burned", burned)
+            .add_attribute("shortfall", claim.demanded_tokens - burned));
+    }
     
-    if approved {
+    if approved && !previously_approved {
         let mut org_info = ORGANIZATIONS.may_load(deps.storage, &claim.organization)?
             .unwrap_or(OrganizationInfo {
                 reputation_score: Uint128::zero(),
@@ -169,142 +721,1455 @@ pub fn execute_finalize_voting(
                 total_returned: Uint128::zero(),
                 name: "".to_string(),
                 emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
             });
         
-        org_info.carbon_credits += claim.demanded_tokens;
+        let mut batch = CreditBatch {
+            claim_id,
+            vintage: vintage_year(claim.time_ended),
+            amount: claim.demanded_tokens,
+        };
+        let seizures = if config.seize_defaulted_issuance {
+            seize_issuance(deps.storage, &claim.organization, &mut org_info, &batch)?
+        } else {
+            vec![]
+        };
+        for (loan_id, lender, seized) in seizures {
+            batch.amount -= seized;
+            messages.extend(credit_token_msg(&config, Cw20ExecuteMsg::Mint {
+                recipient: env.contract.address.to_string(),
+                amount: seized,
+            })?);
+            events.push(Event::new("issuance_seized")
+                .add_attribute("claim_id", claim_id.to_string())
+                .add_attribute("loan_id", loan_id.to_string())
+                .add_attribute("lender", lender)
+                .add_attribute("amount", seized));
+        }
+        org_info.carbon_credits += batch.amount;
         ORGANIZATIONS.save(deps.storage, &claim.organization, &org_info)?;
         
         config.total_carbon_credits += claim.demanded_tokens;
-        CONFIG.save(deps.storage, &config)?;
+        if !batch.amount.is_zero() {
+            add_credit_batches(deps.storage, &claim.organization, &[batch.clone()])?;
+            messages.extend(credit_token_msg(&config, Cw20ExecuteMsg::Mint {
+                recipient: env.contract.address.to_string(),
+                amount: batch.amount,
+            })?);
+        }
     }
-    let voters: Vec<(u64, Addr)> = VOTES
-        .prefix(claim_id)
-        .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .map(|key| key.map(|addr| (claim_id, addr)))
-        .collect::<Result<Vec<_>, _>>()?;
     
     for (_, voter_addr) in voters {
-        let vote = VOTES.load(deps.storage, (claim_id, &voter_addr))?;
-        let vote_correct = (vote == VoteOption::Yes && approved) || (vote == VoteOption::No && !approved);
+        let ballot = VOTES.load(deps.storage, (claim_id, &voter_addr))?;
+        let vote_correct = (ballot.vote == VoteOption::Yes && approved) || (ballot.vote == VoteOption::No && !approved);
         
+        let mut org_info = ORGANIZATIONS.may_load(deps.storage, &voter_addr)?
+            .unwrap_or(OrganizationInfo {
+                reputation_score: Uint128::zero(),
+                carbon_credits: Uint128::zero(),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: "".to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            });
+        let mut record = VoteRecord {
+            claim_id,
+            vote: ballot.vote,
+            weight: ballot.weight,
+            correct: vote_correct,
+            reputation_gained: Uint128::zero(),
+            reputation_lost: Uint128::zero(),
+            bond_slashed: Uint128::zero(),
+        };
         if vote_correct {
-            let mut org_info = ORGANIZATIONS.may_load(deps.storage, &voter_addr)?
-                .unwrap_or(OrganizationInfo {
-                    reputation_score: Uint128::zero(),
-                    carbon_credits: Uint128::zero(),
-                    debt: Uint128::zero(),
-                    times_borrowed: 0,
-                    total_borrowed: Uint128::zero(),
-                    total_returned: Uint128::zero(),
-                    name: "".to_string(),
-                    emissions: Uint128::zero(),
-                });
-            org_info.reputation_score += Uint128::new(1);
-            ORGANIZATIONS.save(deps.storage, &voter_addr, &org_info)?;
+            record.reputation_gained = config.vote_reward;
+            org_info.reputation_score += config.vote_reward;
+        } else {
+            // Reputation never goes below zero
+            record.reputation_lost = config.vote_slash.min(org_info.reputation_score);
+            org_info.reputation_score -= record.reputation_lost;
+
+            // Slashed bond is burned rather than redistributed
+            if let Some(mut verifier) = VERIFIERS.may_load(deps.storage, &voter_addr)? {
+                record.bond_slashed = verifier.bonded_credits * config.bond_slash;
+                if !record.bond_slashed.is_zero() {
+                    verifier.bonded_credits -= record.bond_slashed;
+                    split_batches(&mut verifier.bond_batches, record.bond_slashed);
+                    config.total_carbon_credits = config.total_carbon_credits.saturating_sub(record.bond_slashed);
+                    VERIFIERS.save(deps.storage, &voter_addr, &verifier)?;
+                    // The bond is escrowed here, so the contract burns from its own balance
+                    messages.extend(credit_token_msg(&config, Cw20ExecuteMsg::Burn {
+                        amount: record.bond_slashed,
+                    })?);
+                }
+            }
         }
+        // A first-round voter of an upheld decision keeps what that round earned
+        if let Some(first_round) = VOTE_RECORDS.may_load(deps.storage, (&voter_addr, claim_id))? {
+            record.reputation_gained += first_round.reputation_gained;
+            record.reputation_lost += first_round.reputation_lost;
+            record.bond_slashed += first_round.bond_slashed;
+        }
+        ORGANIZATIONS.save(deps.storage, &voter_addr, &org_info)?;
+        VOTE_RECORDS.save(deps.storage, (&voter_addr, claim_id), &record)?;
+
+        events.push(Event::new("vote_outcome")
+            .add_attribute("claim_id", claim_id.to_string())
+            .add_attribute("voter", voter_addr)
+            .add_attribute("correct", vote_correct.to_string())
+            .add_attribute("reputation_gained", record.reputation_gained)
+            .add_attribute("reputation_lost", record.reputation_lost)
+            .add_attribute("bond_slashed", record.bond_slashed));
     }
+    if !approved {
+        unindex_claim(deps.storage, &claim)?;
+    }
+    CONFIG.save(deps.storage, &config)?;
     CLAIMS.save(deps.storage, claim_id, &claim)?;
     
     Ok(Response::new()
+        .add_messages(messages)
+        .add_events(events)
         .add_attribute("method", "finalize_voting")
         .add_attribute("claim_id", claim_id.to_string())
         .add_attribute("status", format!("{:?}", claim.status)))
 }
 
-pub fn execute_request_tokens(
-    deps: DepsMut,
-    env: Env,  // Changed from _env to env since we'll need it for timestamp
-    info: MessageInfo,
-    lender: Addr,
-    amount: Uint128,
-) -> Result<Response, ContractError> {
-    let mut lender_info = ORGANIZATIONS.may_load(deps.storage, &lender)?
-        .unwrap_or(OrganizationInfo {
-            reputation_score: Uint128::zero(),
-            carbon_credits: Uint128::zero(),
-            debt: Uint128::zero(),
-            times_borrowed: 0,
-            total_borrowed: Uint128::zero(),
-            total_returned: Uint128::zero(),
-            name: "".to_string(),
-            emissions: Uint128::zero(),
-        });
-    let mut borrower_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
-        .unwrap_or(OrganizationInfo {
-            reputation_score: Uint128::zero(),
-            carbon_credits: Uint128::zero(),
-            debt: Uint128::zero(),
-            times_borrowed: 0,
-            total_borrowed: Uint128::zero(),
-            total_returned: Uint128::zero(),
-            name: "".to_string(),
-            emissions: Uint128::zero(),
-        });
+const SQUARE_METERS_PER_HECTARE: u128 = 10_000;
+const SECONDS_PER_YEAR: u128 = 31_536_000;
 
-    let borrower_emissions = u32::try_from(borrower_info.emissions.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for emissions")))?;
-    let borrower_returned = u32::try_from(borrower_info.total_returned.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for total_returned")))?;
-    let borrower_total_borrowed = u32::try_from(borrower_info.total_borrowed.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for total_borrowed")))?;
-    let borrower_debt = u32::try_from(borrower_info.debt.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for debt")))?;
-    let borrower_credits = u32::try_from(borrower_info.carbon_credits.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for carbon_credits")))?;
-    let borrower_reputation = u32::try_from(borrower_info.reputation_score.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for reputation_score")))?;
-    let lender_credits = u32::try_from(lender_info.carbon_credits.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for lender carbon_credits")))?;
-    let lender_debt = u32::try_from(lender_info.debt.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for lender debt")))?;
-    
-    let (eligibility_score, proof_data) = eligibility_proof(
-        borrower_emissions,
-        borrower_returned,
-        borrower_total_borrowed,
-        borrower_debt,
-        borrower_credits,
-        borrower_reputation,
-        lender_credits,
-        lender_debt,
-    );
-    let proof_hex = hex::encode(&proof_data);
-    let mut lend_request_counter = LEND_REQUEST_COUNTER.load(deps.storage)?;
-    let config = CONFIG.load(deps.storage)?;
-    let lendrequest = LendRequest {
-        id: lend_request_counter,
-        borrower: info.sender.clone(),
-        lender: lender.clone(),
-        status: LentStatus::Active,
-        eligibility_score:   Uint128::from(eligibility_score as u128),
-        proof_data: proof_hex,
-        time: env.block.time.seconds(), 
-        amount: amount,
-    };
-    
-    // Save the lend request with the current counter as key
-    LEND_REQUESTS.save(deps.storage, lend_request_counter, &lendrequest)?;
-    
-    // Increment and save the counter
-    lend_request_counter += 1;
-   
-    LEND_REQUEST_COUNTER.save(deps.storage, &lend_request_counter)?;
-    
-    Ok(Response::new()
-        .add_attribute("method", "request_tokens")
-        .add_attribute("request_tokens_id", lend_request_counter.to_string())
-        .add_attribute("borrower", info.sender)
-        .add_attribute("lender", lender)
-        .add_attribute("requested_tokens", amount.to_string()))
+/// Most credits a claim may demand: hectares x years x the methodology's yearly rate.
+fn claim_cap(methodology: &Methodology, area: Uint128, period: u64) -> Uint128 {
+    // Square meter seconds, divided down to hectare years only after the rate is applied
+    let square_meter_seconds = area.full_mul(Uint128::from(period));
+    let scaled = square_meter_seconds * cosmwasm_std::Uint256::from(methodology.credits_per_hectare_year.atomics())
+        / cosmwasm_std::Uint256::from(Decimal::one().atomics())
+        / cosmwasm_std::Uint256::from(SQUARE_METERS_PER_HECTARE * SECONDS_PER_YEAR);
+    Uint128::try_from(scaled).unwrap_or(Uint128::MAX)
 }
 
+fn index_claim(storage: &mut dyn cosmwasm_std::Storage, claim: &Claim) -> Result<(), ContractError> {
+    for (x, y) in covering_tiles(&claim.polygon)? {
+        CLAIM_TILES.save(storage, (x, y, claim.id), &cosmwasm_std::Empty {})?;
+    }
+    Ok(())
+}
+
+fn unindex_claim(storage: &mut dyn cosmwasm_std::Storage, claim: &Claim) -> Result<(), ContractError> {
+    for (x, y) in covering_tiles(&claim.polygon)? {
+        CLAIM_TILES.remove(storage, (x, y, claim.id));
+    }
+    Ok(())
+}
+
+/// Ids of indexed claims whose polygon and time range both overlap the given ones.
+fn conflicting_claims(deps: Deps, polygon: &Polygon, time_started: u64, time_ended: u64) -> Result<Vec<u64>, ContractError> {
+    let mut candidates = std::collections::BTreeSet::new();
+    for (x, y) in covering_tiles(polygon)? {
+        for claim_id in CLAIM_TILES.prefix((x, y)).keys(deps.storage, None, None, Order::Ascending) {
+            candidates.insert(claim_id?);
+        }
+    }
+    let mut conflicts = vec![];
+    for claim_id in candidates {
+        let claim = CLAIMS.load(deps.storage, claim_id)?;
+        if claim.time_started < time_ended
+            && time_started < claim.time_ended
+            && polygons_overlap(&claim.polygon, polygon)
+        {
+            conflicts.push(claim_id);
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Removes up to `amount` credits from the holder's batches, oldest claim first,
+/// and returns what was taken. Any remainder comes from unattributed credits.
+fn take_credit_batches(storage: &mut dyn cosmwasm_std::Storage, holder: &Addr, amount: Uint128) -> StdResult<Vec<CreditBatch>> {
+    let mut remaining = amount;
+    let mut taken = vec![];
+    let batches = CREDIT_BATCHES
+        .prefix(holder)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (claim_id, mut batch) in batches {
+        if remaining.is_zero() {
+            break;
+        }
+        let amount = batch.amount.min(remaining);
+        remaining -= amount;
+        batch.amount -= amount;
+        if batch.amount.is_zero() {
+            CREDIT_BATCHES.remove(storage, (holder, claim_id));
+        } else {
+            CREDIT_BATCHES.save(storage, (holder, claim_id), &batch)?;
+        }
+        taken.push(CreditBatch { amount, ..batch });
+    }
+    Ok(taken)
+}
+
+/// Splits up to `amount` off escrowed batches, oldest claim first.
+fn split_batches(batches: &mut Vec<CreditBatch>, amount: Uint128) -> Vec<CreditBatch> {
+    let mut remaining = amount;
+    let mut taken = vec![];
+    for batch in batches.iter_mut() {
+        if remaining.is_zero() {
+            break;
+        }
+        let amount = batch.amount.min(remaining);
+        remaining -= amount;
+        batch.amount -= amount;
+        taken.push(CreditBatch { amount, ..batch.clone() });
+    }
+    batches.retain(|batch| !batch.amount.is_zero());
+    taken
+}
+
+/// Adds batches to an escrow, keeping it ordered by claim.
+fn merge_batches(batches: &mut Vec<CreditBatch>, added: &[CreditBatch]) {
+    for batch in added {
+        match batches.binary_search_by_key(&batch.claim_id, |held| held.claim_id) {
+            Ok(index) => batches[index].amount += batch.amount,
+            Err(index) => batches.insert(index, batch.clone()),
+        }
+    }
+}
+
+fn add_credit_batches(storage: &mut dyn cosmwasm_std::Storage, holder: &Addr, batches: &[CreditBatch]) -> StdResult<()> {
+    for batch in batches {
+        let mut held = CREDIT_BATCHES.may_load(storage, (holder, batch.claim_id))?
+            .unwrap_or(CreditBatch { amount: Uint128::zero(), ..batch.clone() });
+        held.amount += batch.amount;
+        CREDIT_BATCHES.save(storage, (holder, batch.claim_id), &held)?;
+    }
+    Ok(())
+}
+
+/// Calendar year (UTC) of a unix timestamp.
+pub fn vintage_year(timestamp: u64) -> u32 {
+    // Civil-from-days conversion for the proleptic Gregorian calendar
+    let days = (timestamp / 86400) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400;
+    (if month_index >= 10 { year + 1 } else { year }) as u32
+}
+
+/// Returns an escrowed dispute bond to the disputer.
+fn refund_dispute_bond(storage: &mut dyn cosmwasm_std::Storage, dispute: &Dispute) -> StdResult<()> {
+    let mut org_info = ORGANIZATIONS.load(storage, &dispute.disputer)?;
+    org_info.carbon_credits += dispute.bond;
+    ORGANIZATIONS.save(storage, &dispute.disputer, &org_info)?;
+    add_credit_batches(storage, &dispute.disputer, &dispute.bond_batches)
+}
+
+/// Undoes the rewards and slashes of a disputed claim's first round once the dispute
+/// round overturns it, so each voter is judged only against the final outcome.
+fn settle_first_round(
+    storage: &mut dyn cosmwasm_std::Storage,
+    config: &mut Config,
+    contract: &Addr,
+    claim_id: u64,
+    voters: &[Addr],
+) -> StdResult<Vec<WasmMsg>> {
+    let mut messages = vec![];
+    for voter_addr in voters {
+        let record = match VOTE_RECORDS.may_load(storage, (voter_addr, claim_id))? {
+            Some(record) => record,
+            None => continue,
+        };
+        VOTE_RECORDS.remove(storage, (voter_addr, claim_id));
+        let mut org_info = ORGANIZATIONS.load(storage, voter_addr)?;
+        org_info.reputation_score = org_info.reputation_score.saturating_sub(record.reputation_gained) + record.reputation_lost;
+        if record.bond_slashed.is_zero() {
+            ORGANIZATIONS.save(storage, voter_addr, &org_info)?;
+            continue;
+        }
+        // The slashed bond was burned, so it is minted again: back into escrow,
+        // or to the voter's balance if they are no longer a verifier. The burned
+        // batches are not recorded, so the restored credits are unattributed.
+        config.total_carbon_credits += record.bond_slashed;
+        match VERIFIERS.may_load(storage, voter_addr)? {
+            Some(mut verifier) => {
+                verifier.bonded_credits += record.bond_slashed;
+                VERIFIERS.save(storage, voter_addr, &verifier)?;
+            }
+            None => org_info.carbon_credits += record.bond_slashed,
+        }
+        ORGANIZATIONS.save(storage, voter_addr, &org_info)?;
+        messages.extend(credit_token_msg(config, Cw20ExecuteMsg::Mint {
+            recipient: contract.to_string(),
+            amount: record.bond_slashed,
+        })?);
+    }
+    Ok(messages)
+}
+
+pub fn execute_dispute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    claim_id: u64,
+) -> Result<Response, ContractError> {
+    let mut claim = CLAIMS.load(deps.storage, claim_id)
+        .map_err(|_| ContractError::ClaimNotFound {})?;
+    if claim.status != ClaimStatus::Approved && claim.status != ClaimStatus::Rejected {
+        return Err(ContractError::ClaimNotDisputable {});
+    }
+    if DISPUTES.has(deps.storage, claim_id) {
+        return Err(ContractError::AlreadyDisputed {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let finalized_at = claim.finalized_at.unwrap_or_default();
+    if env.block.time.seconds() > finalized_at + config.challenge_period {
+        return Err(ContractError::ChallengePeriodEnded {});
+    }
+    // A rejection was dropped from the index, so land claimed since could be credited twice
+    if claim.status == ClaimStatus::Rejected {
+        if let Some(conflict) = conflicting_claims(deps.as_ref(), &claim.polygon, claim.time_started, claim.time_ended)?.first() {
+            return Err(ContractError::OverlappingClaim { claim_id: *conflict });
+        }
+    }
+
+    // The bond is held by the contract until the dispute is resolved
+    let mut org_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotEnoughCredits {})?;
+    if org_info.carbon_credits < config.dispute_bond {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    org_info.carbon_credits -= config.dispute_bond;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+    let bond_batches = take_credit_batches(deps.storage, &info.sender, config.dispute_bond)?;
+
+    // Re-open voting from a clean slate
+    let original_voters: Vec<Addr> = VOTES
+        .prefix(claim_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for voter_addr in &original_voters {
+        VOTES.remove(deps.storage, (claim_id, voter_addr));
+    }
+    let dispute = Dispute {
+        claim_id,
+        disputer: info.sender.clone(),
+        bond: config.dispute_bond,
+        original_status: claim.status.clone(),
+        opened_at: env.block.time.seconds(),
+        status: DisputeStatus::Open,
+        bond_batches,
+        original_voters,
+    };
+    DISPUTES.save(deps.storage, claim_id, &dispute)?;
+    // A disputed rejection competes for its area again until resolved
+    index_claim(deps.storage, &claim)?;
+    claim.status = ClaimStatus::Disputed;
+    claim.yes_votes = Uint128::zero();
+    claim.no_votes = Uint128::zero();
+    claim.voting_end_time = env.block.time.seconds() + config.voting_period;
+    claim.reveal_end_time = if claim.commit_reveal {
+        claim.voting_end_time + config.reveal_period
+    } else {
+        claim.voting_end_time
+    };
+    CLAIMS.save(deps.storage, claim_id, &claim)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "dispute_claim")
+        .add_attribute("claim_id", claim_id.to_string())
+        .add_attribute("disputer", info.sender)
+        .add_attribute("bond", config.dispute_bond)
+        .add_attribute("voting_end_time", claim.voting_end_time.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_voting_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    voting_period: Option<u64>,
+    min_voters: Option<u64>,
+    quorum: Option<Decimal>,
+    threshold: Option<Decimal>,
+    voting_power: Option<VotingPowerModel>,
+    verifier_min_reputation: Option<Uint128>,
+    verifier_min_bond: Option<Uint128>,
+    commit_reveal: Option<bool>,
+    reveal_period: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(voting_period) = voting_period {
+        config.voting_period = voting_period;
+    }
+    if let Some(min_voters) = min_voters {
+        config.min_voters = min_voters;
+    }
+    if let Some(quorum) = quorum {
+        config.quorum = quorum;
+    }
+    if let Some(threshold) = threshold {
+        config.threshold = threshold;
+    }
+    if let Some(voting_power) = voting_power {
+        config.voting_power = voting_power;
+    }
+    if let Some(verifier_min_reputation) = verifier_min_reputation {
+        config.verifier_min_reputation = verifier_min_reputation;
+    }
+    if let Some(verifier_min_bond) = verifier_min_bond {
+        config.verifier_min_bond = verifier_min_bond;
+    }
+    if let Some(commit_reveal) = commit_reveal {
+        config.commit_reveal = commit_reveal;
+    }
+    if let Some(reveal_period) = reveal_period {
+        config.reveal_period = reveal_period;
+    }
+    validate_ratios(&[config.quorum, config.threshold])?;
+    validate_dispute_round(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_voting_config")
+        .add_attribute("voting_period", config.voting_period.to_string())
+        .add_attribute("min_voters", config.min_voters.to_string())
+        .add_attribute("quorum", config.quorum.to_string())
+        .add_attribute("threshold", config.threshold.to_string())
+        .add_attribute("voting_power", format!("{:?}", config.voting_power))
+        .add_attribute("verifier_min_reputation", config.verifier_min_reputation)
+        .add_attribute("verifier_min_bond", config.verifier_min_bond)
+        .add_attribute("commit_reveal", config.commit_reveal.to_string())
+        .add_attribute("reveal_period", config.reveal_period.to_string()))
+}
+
+pub fn execute_update_vote_incentives(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    vote_reward: Option<Uint128>,
+    vote_slash: Option<Uint128>,
+    bond_slash: Option<Decimal>,
+    unrevealed_slash: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(vote_reward) = vote_reward {
+        config.vote_reward = vote_reward;
+    }
+    if let Some(vote_slash) = vote_slash {
+        config.vote_slash = vote_slash;
+    }
+    if let Some(bond_slash) = bond_slash {
+        config.bond_slash = bond_slash;
+    }
+    if let Some(unrevealed_slash) = unrevealed_slash {
+        config.unrevealed_slash = unrevealed_slash;
+    }
+    validate_ratios(&[config.bond_slash])?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_vote_incentives")
+        .add_attribute("vote_reward", config.vote_reward)
+        .add_attribute("vote_slash", config.vote_slash)
+        .add_attribute("bond_slash", config.bond_slash.to_string())
+        .add_attribute("unrevealed_slash", config.unrevealed_slash))
+}
+
+pub fn execute_update_dispute_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    challenge_period: Option<u64>,
+    dispute_bond: Option<Uint128>,
+    dispute_min_voters: Option<u64>,
+    dispute_quorum: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(challenge_period) = challenge_period {
+        config.challenge_period = challenge_period;
+    }
+    if let Some(dispute_bond) = dispute_bond {
+        config.dispute_bond = dispute_bond;
+    }
+    if let Some(dispute_min_voters) = dispute_min_voters {
+        config.dispute_min_voters = dispute_min_voters;
+    }
+    if let Some(dispute_quorum) = dispute_quorum {
+        config.dispute_quorum = dispute_quorum;
+    }
+    validate_ratios(&[config.dispute_quorum])?;
+    validate_dispute_round(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_dispute_config")
+        .add_attribute("challenge_period", config.challenge_period.to_string())
+        .add_attribute("dispute_bond", config.dispute_bond)
+        .add_attribute("dispute_min_voters", config.dispute_min_voters.to_string())
+        .add_attribute("dispute_quorum", config.dispute_quorum.to_string()))
+}
+
+pub fn execute_update_claim_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    max_claim_period: u64,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.max_claim_period = max_claim_period;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_claim_config")
+        .add_attribute("max_claim_period", max_claim_period.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_loan_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    max_loan_term: Option<u64>,
+    loan_grace_period: Option<u64>,
+    default_penalty: Option<Uint128>,
+    seize_defaulted_issuance: Option<bool>,
+    min_eligibility_score: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(max_loan_term) = max_loan_term {
+        config.max_loan_term = max_loan_term;
+    }
+    if let Some(loan_grace_period) = loan_grace_period {
+        config.loan_grace_period = loan_grace_period;
+    }
+    if let Some(default_penalty) = default_penalty {
+        config.default_penalty = default_penalty;
+    }
+    if let Some(seize_defaulted_issuance) = seize_defaulted_issuance {
+        config.seize_defaulted_issuance = seize_defaulted_issuance;
+    }
+    if let Some(min_eligibility_score) = min_eligibility_score {
+        config.min_eligibility_score = min_eligibility_score;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_loan_config")
+        .add_attribute("max_loan_term", config.max_loan_term.to_string())
+        .add_attribute("loan_grace_period", config.loan_grace_period.to_string())
+        .add_attribute("default_penalty", config.default_penalty)
+        .add_attribute("seize_defaulted_issuance", config.seize_defaulted_issuance.to_string())
+        .add_attribute("min_eligibility_score", config.min_eligibility_score))
+}
+
+pub fn execute_update_collateral_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collateral: Option<CollateralConfig>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // Requests already made keep the collateral they were created with
+    config.collateral = collateral;
+    CONFIG.save(deps.storage, &config)?;
+
+    let collateral = config.collateral
+        .map(|collateral| format!("{}{} per credit", collateral.ratio, collateral.denom))
+        .unwrap_or_else(|| "none".to_string());
+    Ok(Response::new()
+        .add_attribute("method", "update_collateral_config")
+        .add_attribute("collateral", collateral))
+}
+
+pub fn execute_update_market_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    quote_denoms: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.quote_denoms = quote_denoms;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_market_config")
+        .add_attribute("quote_denoms", config.quote_denoms.join(",")))
+}
+
+pub fn execute_set_methodology(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    methodology: Methodology,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    METHODOLOGIES.save(deps.storage, &methodology.id, &methodology)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_methodology")
+        .add_attribute("methodology", methodology.id)
+        .add_attribute("credits_per_hectare_year", methodology.credits_per_hectare_year.to_string()))
+}
+
+pub fn execute_remove_methodology(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !METHODOLOGIES.has(deps.storage, &id) {
+        return Err(ContractError::MethodologyNotFound {});
+    }
+    // Claims already filed under the methodology keep their cap
+    METHODOLOGIES.remove(deps.storage, &id);
+
+    Ok(Response::new()
+        .add_attribute("method", "remove_methodology")
+        .add_attribute("methodology", id))
+}
+
+pub fn execute_add_verifier(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if VERIFIERS.has(deps.storage, &address) {
+        return Err(ContractError::VerifierExists {});
+    }
+    let verifier = VerifierInfo {
+        bonded_credits: Uint128::zero(),
+        added_at: env.block.time.seconds(),
+        pending_votes: 0,
+        bond_batches: vec![],
+    };
+    VERIFIERS.save(deps.storage, &address, &verifier)?;
+    VERIFIER_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "add_verifier")
+        .add_attribute("verifier", address))
+}
+
+pub fn execute_remove_verifier(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let verifier = VERIFIERS.may_load(deps.storage, &address)?
+        .ok_or(ContractError::NotVerifier {})?;
+
+    // Any remaining bond goes back to the verifier's balance
+    if !verifier.bonded_credits.is_zero() {
+        let mut org_info = ORGANIZATIONS.load(deps.storage, &address)?;
+        org_info.carbon_credits += verifier.bonded_credits;
+        ORGANIZATIONS.save(deps.storage, &address, &org_info)?;
+        add_credit_batches(deps.storage, &address, &verifier.bond_batches)?;
+    }
+    VERIFIERS.remove(deps.storage, &address);
+    VERIFIER_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count - 1) })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "remove_verifier")
+        .add_attribute("verifier", address)
+        .add_attribute("returned_bond", verifier.bonded_credits))
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.credit_token.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::Deposit {} => {
+            // Tokens from outside carry no batch, so deposited credits are unattributed
+            let mut org_info = ORGANIZATIONS.may_load(deps.storage, &sender)?
+                .unwrap_or(OrganizationInfo {
+                    reputation_score: Uint128::zero(),
+                    carbon_credits: Uint128::zero(),
+                    debt: Uint128::zero(),
+                    times_borrowed: 0,
+                    total_borrowed: Uint128::zero(),
+                    total_returned: Uint128::zero(),
+                    name: "".to_string(),
+                    emissions: Uint128::zero(),
+                    interest_owed: Uint128::zero(),
+                    total_interest_paid: Uint128::zero(),
+                });
+            org_info.carbon_credits += wrapper.amount;
+            ORGANIZATIONS.save(deps.storage, &sender, &org_info)?;
+
+            Ok(Response::new()
+                .add_attribute("method", "deposit_credits")
+                .add_attribute("organization", sender)
+                .add_attribute("amount", wrapper.amount))
+        }
+    }
+}
+
+pub fn execute_withdraw_credits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.credit_token.is_none() {
+        return Err(ContractError::NoCreditToken {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    let mut org_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotEnoughCredits {})?;
+    if org_info.carbon_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    org_info.carbon_credits -= amount;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+    // Credits in a wallet are no longer tracked by vintage
+    take_credit_batches(deps.storage, &info.sender, amount)?;
+    let transfer = credit_token_msg(&config, Cw20ExecuteMsg::Transfer {
+        recipient: info.sender.to_string(),
+        amount,
+    })?;
+
+    Ok(Response::new()
+        .add_messages(transfer)
+        .add_attribute("method", "withdraw_credits")
+        .add_attribute("organization", info.sender)
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_transfer_credits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    recipient: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    move_credits(deps, &info.sender, &recipient, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "transfer_credits")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient)
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_approve_credits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: Addr,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    if spender == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    // Approving replaces any previous allowance for the spender
+    let allowance = Allowance { amount, expires };
+    ALLOWANCES.save(deps.storage, (&info.sender, &spender), &allowance)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "approve_credits")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount)
+        .add_attribute("expires", allowance.expires.to_string()))
+}
+
+pub fn execute_revoke_credits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    spender: Addr,
+) -> Result<Response, ContractError> {
+    ALLOWANCES.remove(deps.storage, (&info.sender, &spender));
+
+    Ok(Response::new()
+        .add_attribute("method", "revoke_credits")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender))
+}
+
+pub fn execute_transfer_credits_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    recipient: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut allowance = ALLOWANCES.may_load(deps.storage, (&owner, &info.sender))?
+        .ok_or(ContractError::InsufficientAllowance {})?;
+    if allowance.expires.is_expired(&env.block) {
+        return Err(ContractError::AllowanceExpired {});
+    }
+    if allowance.amount < amount {
+        return Err(ContractError::InsufficientAllowance {});
+    }
+    allowance.amount -= amount;
+    if allowance.amount.is_zero() {
+        ALLOWANCES.remove(deps.storage, (&owner, &info.sender));
+    } else {
+        ALLOWANCES.save(deps.storage, (&owner, &info.sender), &allowance)?;
+    }
+    move_credits(deps, &owner, &recipient, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "transfer_credits_from")
+        .add_attribute("spender", info.sender)
+        .add_attribute("from", owner)
+        .add_attribute("to", recipient)
+        .add_attribute("amount", amount))
+}
+
+/// Moves credits and their oldest batches between organizations.
+fn move_credits(deps: DepsMut, from: &Addr, to: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    if from == to {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    let mut from_info = ORGANIZATIONS.may_load(deps.storage, from)?
+        .ok_or(ContractError::NotEnoughCredits {})?;
+    if from_info.carbon_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    let mut to_info = ORGANIZATIONS.may_load(deps.storage, to)?
+        .unwrap_or(OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::zero(),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        });
+    from_info.carbon_credits -= amount;
+    to_info.carbon_credits += amount;
+    ORGANIZATIONS.save(deps.storage, from, &from_info)?;
+    ORGANIZATIONS.save(deps.storage, to, &to_info)?;
+    let batches = take_credit_batches(deps.storage, from, amount)?;
+    add_credit_batches(deps.storage, to, &batches)?;
+    Ok(())
+}
+
+pub fn execute_retire_credits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    beneficiary: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidRetirementAmount {});
+    }
+    let mut org_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotEnoughCredits {})?;
+    if org_info.carbon_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    org_info.carbon_credits -= amount;
+    // Retired credits offset the organization's reported emissions
+    org_info.emissions = org_info.emissions.saturating_sub(amount);
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.total_carbon_credits = config.total_carbon_credits.saturating_sub(amount);
+    CONFIG.save(deps.storage, &config)?;
+
+    let batches = take_credit_batches(deps.storage, &info.sender, amount)?;
+    let id = RETIREMENT_COUNTER.load(deps.storage)?;
+    let certificate = RetirementCertificate {
+        id,
+        organization: info.sender.clone(),
+        beneficiary,
+        amount,
+        reason,
+        block_height: env.block.height,
+        timestamp: env.block.time.seconds(),
+        batches,
+    };
+    RETIREMENTS.save(deps.storage, id, &certificate)?;
+    ORGANIZATION_RETIREMENTS.save(deps.storage, (&info.sender, id), &cosmwasm_std::Empty {})?;
+    RETIREMENT_COUNTER.save(deps.storage, &(id + 1))?;
+
+    // The retired credits are held here, so the contract burns from its own balance
+    let burn = credit_token_msg(&config, Cw20ExecuteMsg::Burn { amount })?;
+
+    Ok(Response::new()
+        .add_messages(burn)
+        .add_attribute("method", "retire_credits")
+        .add_attribute("organization", info.sender)
+        .add_attribute("certificate_id", id.to_string())
+        .add_attribute("beneficiary", certificate.beneficiary)
+        .add_attribute("amount", amount)
+        .add_attribute("emissions", org_info.emissions))
+}
+
+// Upper bound on resting orders a single placement fills, keeping gas predictable
+pub const MAX_ORDER_MATCHES: usize = 30;
+
+pub fn execute_place_ask(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    price: Uint128,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if price.is_zero() || amount.is_zero() {
+        return Err(ContractError::InvalidOrder {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if !config.quote_denoms.contains(&denom) {
+        return Err(ContractError::UnsupportedDenom { denom });
+    }
+    let mut org_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotEnoughCredits {})?;
+    if org_info.carbon_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    org_info.carbon_credits -= amount;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+
+    let id = ORDER_COUNTER.load(deps.storage)?;
+    ORDER_COUNTER.save(deps.storage, &(id + 1))?;
+    let mut ask = MarketOrder {
+        id,
+        owner: info.sender.clone(),
+        side: OrderSide::Ask,
+        denom,
+        price,
+        amount,
+        remaining: amount,
+        batches: take_credit_batches(deps.storage, &info.sender, amount)?,
+        status: OrderStatus::Open,
+        created_at: env.block.time.seconds(),
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    let mut events = vec![];
+    let mut proceeds = Uint128::zero();
+    let bids = BIDS
+        .sub_prefix(&ask.denom)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_ORDER_MATCHES)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (inverted_price, bid_id) in bids {
+        if ask.remaining.is_zero() || u128::MAX - inverted_price < price.u128() {
+            break;
+        }
+        // Trades execute at the resting order's price
+        let mut bid = MARKET_ORDERS.load(deps.storage, bid_id)?;
+        let fill = ask.remaining.min(bid.remaining);
+        proceeds += bid.price.checked_mul(fill)?;
+        fill_ask(deps.storage, &mut ask, &bid.owner, fill)?;
+        bid.remaining -= fill;
+        save_order(deps.storage, &mut bid)?;
+        events.push(trade_event(&ask, &bid, bid.price, fill));
+    }
+    if !proceeds.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(proceeds.u128(), ask.denom.clone()),
+        }));
+    }
+    // Matching stops at MAX_ORDER_MATCHES, a remainder that still crosses the book is handed back
+    if crosses_book(deps.storage, &ask)? {
+        messages.extend(release_order(deps.storage, &mut ask)?);
+    } else {
+        save_order(deps.storage, &mut ask)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_events(events)
+        .add_attribute("method", "place_ask")
+        .add_attribute("order_id", id.to_string())
+        .add_attribute("seller", info.sender)
+        .add_attribute("denom", ask.denom)
+        .add_attribute("price", price)
+        .add_attribute("amount", amount)
+        .add_attribute("remaining", ask.remaining))
+}
+
+pub fn execute_place_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price: Uint128,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if price.is_zero() || amount.is_zero() {
+        return Err(ContractError::InvalidOrder {});
+    }
+    // The full bid is escrowed up front, the denom of the payment selects the book
+    let expected = price.checked_mul(amount)?;
+    let denom = match info.funds.as_slice() {
+        [coin] if coin.amount == expected => coin.denom.clone(),
+        _ => return Err(ContractError::InvalidFunds { expected }),
+    };
+    let config = CONFIG.load(deps.storage)?;
+    if !config.quote_denoms.contains(&denom) {
+        return Err(ContractError::UnsupportedDenom { denom });
+    }
 
-pub fn execute_lend_tokens(
+    let id = ORDER_COUNTER.load(deps.storage)?;
+    ORDER_COUNTER.save(deps.storage, &(id + 1))?;
+    let mut bid = MarketOrder {
+        id,
+        owner: info.sender.clone(),
+        side: OrderSide::Bid,
+        denom,
+        price,
+        amount,
+        remaining: amount,
+        batches: vec![],
+        status: OrderStatus::Open,
+        created_at: env.block.time.seconds(),
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut events = vec![];
+    let mut refund = Uint128::zero();
+    let asks = ASKS
+        .sub_prefix(&bid.denom)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_ORDER_MATCHES)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (ask_price, ask_id) in asks {
+        if bid.remaining.is_zero() || ask_price > price.u128() {
+            break;
+        }
+        // Trades execute at the resting order's price, the buyer gets the difference back
+        let mut ask = MARKET_ORDERS.load(deps.storage, ask_id)?;
+        let fill = bid.remaining.min(ask.remaining);
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: ask.owner.to_string(),
+            amount: coins(ask.price.checked_mul(fill)?.u128(), bid.denom.clone()),
+        }));
+        refund += (price - ask.price).checked_mul(fill)?;
+        fill_ask(deps.storage, &mut ask, &info.sender, fill)?;
+        bid.remaining -= fill;
+        save_order(deps.storage, &mut ask)?;
+        events.push(trade_event(&ask, &bid, ask.price, fill));
+    }
+    if !refund.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(refund.u128(), bid.denom.clone()),
+        }));
+    }
+    if crosses_book(deps.storage, &bid)? {
+        messages.extend(release_order(deps.storage, &mut bid)?);
+    } else {
+        save_order(deps.storage, &mut bid)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_events(events)
+        .add_attribute("method", "place_bid")
+        .add_attribute("order_id", id.to_string())
+        .add_attribute("buyer", info.sender)
+        .add_attribute("denom", bid.denom)
+        .add_attribute("price", price)
+        .add_attribute("amount", amount)
+        .add_attribute("remaining", bid.remaining))
+}
+
+pub fn execute_cancel_order(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let mut order = MARKET_ORDERS.may_load(deps.storage, order_id)?
+        .ok_or(ContractError::OrderNotFound {})?;
+    if info.sender != order.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if order.status != OrderStatus::Open {
+        return Err(ContractError::OrderNotOpen {});
+    }
+
+    let refund = release_order(deps.storage, &mut order)?;
+
+    Ok(Response::new()
+        .add_messages(refund)
+        .add_attribute("method", "cancel_order")
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("owner", info.sender)
+        .add_attribute("remaining", order.remaining))
+}
+
+/// Cancels an order, releasing whatever is still escrowed for its unfilled part.
+fn release_order(storage: &mut dyn cosmwasm_std::Storage, order: &mut MarketOrder) -> StdResult<Option<CosmosMsg>> {
+    let refund = match order.side {
+        OrderSide::Ask => {
+            let mut org_info = ORGANIZATIONS.load(storage, &order.owner)?;
+            org_info.carbon_credits += order.remaining;
+            ORGANIZATIONS.save(storage, &order.owner, &org_info)?;
+            add_credit_batches(storage, &order.owner, &order.batches)?;
+            None
+        }
+        OrderSide::Bid => Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: order.owner.to_string(),
+            amount: coins(order.price.checked_mul(order.remaining)?.u128(), order.denom.clone()),
+        })),
+    };
+    order_book(&order.side).remove(storage, (&order.denom, book_price(order), order.id));
+    order.batches = vec![];
+    order.status = OrderStatus::Cancelled;
+    MARKET_ORDERS.save(storage, order.id, order)?;
+    Ok(refund)
+}
+
+/// Whether an unfilled order would still match the best resting order on the other side.
+fn crosses_book(storage: &dyn cosmwasm_std::Storage, order: &MarketOrder) -> StdResult<bool> {
+    if order.remaining.is_zero() {
+        return Ok(false);
+    }
+    let opposite = match order.side {
+        OrderSide::Ask => OrderSide::Bid,
+        OrderSide::Bid => OrderSide::Ask,
+    };
+    let best = order_book(&opposite)
+        .sub_prefix(&order.denom)
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?;
+    Ok(match (best, &order.side) {
+        (Some((inverted_price, _)), OrderSide::Ask) => u128::MAX - inverted_price >= order.price.u128(),
+        (Some((price, _)), OrderSide::Bid) => price <= order.price.u128(),
+        (None, _) => false,
+    })
+}
+
+fn order_book(side: &OrderSide) -> &'static Map<'static, (&'static str, u128, u64), cosmwasm_std::Empty> {
+    match side {
+        OrderSide::Ask => &ASKS,
+        OrderSide::Bid => &BIDS,
+    }
+}
+
+// Book key price, inverted for bids so both books iterate best price first
+fn book_price(order: &MarketOrder) -> u128 {
+    match order.side {
+        OrderSide::Ask => order.price.u128(),
+        OrderSide::Bid => u128::MAX - order.price.u128(),
+    }
+}
+
+/// Stores an order, keeping it in the book while it has an unfilled remainder.
+fn save_order(storage: &mut dyn cosmwasm_std::Storage, order: &mut MarketOrder) -> StdResult<()> {
+    let key = (order.denom.as_str(), book_price(order), order.id);
+    if order.remaining.is_zero() {
+        order.status = OrderStatus::Filled;
+        order_book(&order.side).remove(storage, key);
+    } else {
+        order_book(&order.side).save(storage, key, &cosmwasm_std::Empty {})?;
+    }
+    MARKET_ORDERS.save(storage, order.id, order)
+}
+
+/// Releases `amount` escrowed credits of an ask, oldest batches first, to the buyer.
+fn fill_ask(storage: &mut dyn cosmwasm_std::Storage, ask: &mut MarketOrder, buyer: &Addr, amount: Uint128) -> StdResult<()> {
+    let bought = split_batches(&mut ask.batches, amount);
+    ask.remaining -= amount;
+
+    let mut buyer_info = ORGANIZATIONS.may_load(storage, buyer)?
+        .unwrap_or(OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::zero(),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        });
+    buyer_info.carbon_credits += amount;
+    ORGANIZATIONS.save(storage, buyer, &buyer_info)?;
+    add_credit_batches(storage, buyer, &bought)
+}
+
+fn trade_event(ask: &MarketOrder, bid: &MarketOrder, price: Uint128, amount: Uint128) -> Event {
+    Event::new("trade")
+        .add_attribute("ask_id", ask.id.to_string())
+        .add_attribute("bid_id", bid.id.to_string())
+        .add_attribute("seller", ask.owner.to_string())
+        .add_attribute("buyer", bid.owner.to_string())
+        .add_attribute("denom", ask.denom.clone())
+        .add_attribute("price", price)
+        .add_attribute("amount", amount)
+}
+
+pub fn execute_bond_credits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    let mut verifier = VERIFIERS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotVerifier {})?;
+    let mut org_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotEnoughCredits {})?;
+    if org_info.carbon_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    org_info.carbon_credits -= amount;
+    verifier.bonded_credits += amount;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+    let batches = take_credit_batches(deps.storage, &info.sender, amount)?;
+    merge_batches(&mut verifier.bond_batches, &batches);
+    VERIFIERS.save(deps.storage, &info.sender, &verifier)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "bond_credits")
+        .add_attribute("verifier", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("bonded_credits", verifier.bonded_credits))
+}
+
+pub fn execute_unbond_credits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    let mut verifier = VERIFIERS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotVerifier {})?;
+    if verifier.bonded_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    // Unbonding ahead of finalization would dodge the slash for a wrong vote
+    if verifier.pending_votes > 0 {
+        return Err(ContractError::BondLocked {});
+    }
+    let mut org_info = ORGANIZATIONS.load(deps.storage, &info.sender)?;
+    verifier.bonded_credits -= amount;
+    org_info.carbon_credits += amount;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+    let batches = split_batches(&mut verifier.bond_batches, amount);
+    add_credit_batches(deps.storage, &info.sender, &batches)?;
+    VERIFIERS.save(deps.storage, &info.sender, &verifier)?;
+
+
+    Ok(Response::new()
+        .add_attribute("method", "unbond_credits")
+        .add_attribute("verifier", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("bonded_credits", verifier.bonded_credits))
+}
+
+/// In collateral mode the borrower escrows native funds in proportion to the amount.
+fn escrow_collateral(config: &Config, funds: &[Coin], amount: Uint128) -> Result<Option<Coin>, ContractError> {
+    match &config.collateral {
+        Some(collateral_config) => {
+            let required = Coin {
+                denom: collateral_config.denom.clone(),
+                amount: amount * collateral_config.ratio,
+            };
+            match funds {
+                [coin] if coin.denom == required.denom && coin.amount >= required.amount => Ok(Some(coin.clone())),
+                [] if required.amount.is_zero() => Ok(None),
+                _ => Err(ContractError::InsufficientCollateral { required: required.to_string() }),
+            }
+        }
+        None if funds.is_empty() => Ok(None),
+        None => Err(ContractError::UnexpectedFunds {}),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_request_tokens(
+    deps: DepsMut,
+    env: Env,  // Changed from _env to env since we'll need it for timestamp
+    info: MessageInfo,
+    lender: Addr,
+    amount: Uint128,
+    interest_rate: Option<Decimal>,
+    term: u64,
+    proof: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if term == 0 || term > config.max_loan_term {
+        return Err(ContractError::InvalidLoanTerm { max: config.max_loan_term });
+    }
+    let collateral = escrow_collateral(&config, &info.funds, amount)?;
+    let lender_info = ORGANIZATIONS.may_load(deps.storage, &lender)?
+        .unwrap_or(OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::zero(),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        });
+
+    // The proof shows the borrower's attested figures meet the threshold
+    let (eligibility_score, proof_data) = match proof {
+        Some(proof) => {
+            let statement = eligibility_statement(deps.storage, &info.sender, lender_info.carbon_credits, lender_info.debt, config.min_eligibility_score)?;
+            check_eligibility_proof(deps.storage, &info.sender, &proof, &statement)?;
+            (config.min_eligibility_score, hex::encode(proof.as_slice()))
+        }
+        // Left for the borrower to prove with VerifyProof
+        None => (Uint128::zero(), String::new()),
+    };
+    let mut lend_request_counter = LEND_REQUEST_COUNTER.load(deps.storage)?;
+    let lendrequest = LendRequest {
+        id: lend_request_counter,
+        borrower: info.sender.clone(),
+        lender: lender.clone(),
+        status: LentStatus::Active,
+        eligibility_score,
+        proof_data,
+        time: env.block.time.seconds(), 
+        amount,
+        interest_rate,
+        term,
+        maturity: None,
+        outstanding_principal: Uint128::zero(),
+        accrued_interest: Uint128::zero(),
+        accrued_at: 0,
+        defaulted_at: None,
+        collateral: collateral.clone(),
+        pool_id: None,
+    };
+    
+    // Save the lend request with the current counter as key
+    LEND_REQUESTS.save(deps.storage, lend_request_counter, &lendrequest)?;
+    
+    // Increment and save the counter
+    lend_request_counter += 1;
+   
+    LEND_REQUEST_COUNTER.save(deps.storage, &lend_request_counter)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "request_tokens")
+        .add_attribute("request_tokens_id", lend_request_counter.to_string())
+        .add_attribute("borrower", info.sender)
+        .add_attribute("lender", lender)
+        .add_attribute("requested_tokens", amount.to_string())
+        .add_attribute("collateral", collateral.map(|coin| coin.to_string()).unwrap_or_else(|| "none".to_string())))
+}
+
+
+pub fn execute_cancel_lend_request(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     lend_request_id: u64,
+) -> Result<Response, ContractError> {
+    let mut lend_request = LEND_REQUESTS.may_load(deps.storage, lend_request_id)?
+        .ok_or(ContractError::RequestNotFound {})?;
+    if lend_request.borrower != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    // Only requests the lender has not answered yet can be withdrawn
+    if lend_request.status != LentStatus::Active {
+        return Err(ContractError::RequestNotActive {});
+    }
+    lend_request.status = LentStatus::Cancelled;
+    let refund = lend_request.collateral.take().map(|coin| BankMsg::Send {
+        to_address: lend_request.borrower.to_string(),
+        amount: vec![coin],
+    });
+    LEND_REQUESTS.save(deps.storage, lend_request_id, &lend_request)?;
+
+    Ok(Response::new()
+        .add_messages(refund)
+        .add_attribute("method", "cancel_lend_request")
+        .add_attribute("borrower", info.sender)
+        .add_attribute("request_id", lend_request_id.to_string()))
+}
+
+pub fn execute_lend_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lend_request_id: u64,
     response: String,  // "accepted" or "denied" response
+    interest_rate: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let lend_request = LEND_REQUESTS.load(deps.storage, lend_request_id)
         .map_err(|_| ContractError::RequestNotFound {})?;
@@ -322,10 +2187,14 @@ pub fn execute_lend_tokens(
     let mut updated_request = lend_request.clone();
     if response_lowercase == "denied" {
         updated_request.status = LentStatus::Rejected;
+        let refund = updated_request.collateral.take().map(|coin| BankMsg::Send {
+            to_address: updated_request.borrower.to_string(),
+            amount: vec![coin],
+        });
         LEND_REQUESTS.save(deps.storage, lend_request_id, &updated_request)?;
-        updated_request.status = LentStatus::Rejected;
         
         return Ok(Response::new()
+            .add_messages(refund)
             .add_attribute("method", "lend_tokens")
             .add_attribute("lender", info.sender)
             .add_attribute("borrower", updated_request.borrower)
@@ -333,7 +2202,18 @@ pub fn execute_lend_tokens(
             .add_attribute("response", "denied"));
     }
 
-    // If accepted, process the lending
+    // Only borrowers with a verified eligibility proof can be lent to
+    if updated_request.proof_data.is_empty() {
+        return Err(ContractError::ProofNotVerified {});
+    }
+    // If accepted, process the lending at the borrower's rate or the one the lender sets now
+    let interest_rate = match (updated_request.interest_rate, interest_rate) {
+        (Some(requested), None) => requested,
+        (None, Some(offered)) => offered,
+        (Some(requested), Some(offered)) if requested == offered => requested,
+        (None, None) => return Err(ContractError::MissingInterestRate {}),
+        _ => return Err(ContractError::InterestRateConflict {}),
+    };
     let mut lender_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
         .unwrap_or(OrganizationInfo {
             reputation_score: Uint128::zero(),
@@ -344,6 +2224,8 @@ pub fn execute_lend_tokens(
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
     
     let mut borrower_info = ORGANIZATIONS.may_load(deps.storage, &updated_request.borrower)?
@@ -356,6 +2238,8 @@ pub fn execute_lend_tokens(
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
     
     if lender_info.carbon_credits < updated_request.amount {
@@ -367,10 +2251,16 @@ pub fn execute_lend_tokens(
     borrower_info.times_borrowed += 1;
     borrower_info.total_borrowed += updated_request.amount;
     updated_request.status = LentStatus::Approved;
+    updated_request.interest_rate = Some(interest_rate);
+    updated_request.maturity = Some(env.block.time.seconds() + updated_request.term);
+    updated_request.outstanding_principal = updated_request.amount;
+    updated_request.accrued_at = env.block.time.seconds();
 
     ORGANIZATIONS.save(deps.storage, &info.sender, &lender_info)?;
     ORGANIZATIONS.save(deps.storage, &updated_request.borrower, &borrower_info)?;
     LEND_REQUESTS.save(deps.storage, lend_request_id, &updated_request)?;
+    let batches = take_credit_batches(deps.storage, &info.sender, updated_request.amount)?;
+    add_credit_batches(deps.storage, &updated_request.borrower, &batches)?;
     
     Ok(Response::new()
         .add_attribute("method", "lend_tokens")
@@ -378,17 +2268,192 @@ pub fn execute_lend_tokens(
         .add_attribute("borrower", updated_request.borrower)
         .add_attribute("request_id", lend_request_id.to_string())
         .add_attribute("amount", updated_request.amount)
+        .add_attribute("interest_rate", interest_rate.to_string())
+        .add_attribute("maturity", updated_request.maturity.unwrap_or_default().to_string())
         .add_attribute("response", "accepted"))
 }
-pub fn execute_verify_eligibility(
+
+fn figure(value: Uint128, name: &str) -> Result<u64, ContractError> {
+    u64::try_from(value.u128())
+        .map_err(|_| ContractError::Std(StdError::generic_err(format!("Conversion error for {}", name))))
+}
+
+// Public inputs of a proof that the borrower's attested figures score at least `threshold`
+// against a lender's figures; a pool stands in for the lender with the amount drawn and no debt.
+fn eligibility_statement(
+    storage: &dyn cosmwasm_std::Storage,
+    borrower: &Addr,
+    lender_credits: Uint128,
+    lender_debt: Uint128,
+    threshold: Uint128,
+) -> Result<Vec<Vec<u8>>, ContractError> {
+    let commitment = ELIGIBILITY_COMMITMENTS.may_load(storage, borrower)?
+        .ok_or(ContractError::CommitmentNotSet {})?;
+    if !commitment.attested {
+        return Err(ContractError::CommitmentNotAttested {});
+    }
+    let threshold = u64::try_from(threshold.u128())
+        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for eligibility threshold")))?;
+    eligibility_public_inputs(
+        commitment.commitment.as_slice(),
+        figure(lender_credits, "lender carbon_credits")?,
+        figure(lender_debt, "lender debt")?,
+        threshold,
+    )
+    .map_err(|_| ContractError::InvalidProof {})
+}
+
+/// Checks a proof of the statement, using up the borrower's attested commitment.
+fn check_eligibility_proof(storage: &mut dyn cosmwasm_std::Storage, borrower: &Addr, proof: &Binary, statement: &[Vec<u8>]) -> Result<(), ContractError> {
+    let verifying_key = VERIFYING_KEY.may_load(storage)?
+        .ok_or(ContractError::VerifyingKeyNotSet {})?;
+    if verify_proof(verifying_key.as_slice(), proof.as_slice(), statement) != Ok(true) {
+        return Err(ContractError::InvalidProof {});
+    }
+    // The figures behind an attestation change with the loan, so each one backs a single proof
+    ELIGIBILITY_COMMITMENTS.remove(storage, borrower);
+    Ok(())
+}
+
+pub fn execute_commit_eligibility(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    if !is_field_element(commitment.as_slice()) {
+        return Err(ContractError::InvalidEligibilityCommitment {});
+    }
+    // Proofs already verified stand; new ones wait for this commitment to be attested
+    let pending = EligibilityCommitment { commitment: commitment.clone(), attested: false };
+    ELIGIBILITY_COMMITMENTS.save(deps.storage, &info.sender, &pending)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "commit_eligibility")
+        .add_attribute("borrower", info.sender)
+        .add_attribute("commitment", commitment.to_base64()))
+}
+
+pub fn execute_attest_eligibility(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
     borrower: Addr,
-    lender: Addr,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // The owner vouches for figures and a salt the borrower showed it off-chain, and names the
+    // commitment it checked so a later one from the borrower is not attested by mistake
+    let mut pending = ELIGIBILITY_COMMITMENTS.may_load(deps.storage, &borrower)?
+        .ok_or(ContractError::CommitmentNotSet {})?;
+    if pending.commitment != commitment {
+        return Err(ContractError::CommitmentMismatch {});
+    }
+    pending.attested = true;
+    ELIGIBILITY_COMMITMENTS.save(deps.storage, &borrower, &pending)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "attest_eligibility")
+        .add_attribute("borrower", borrower))
+}
+
+pub fn execute_verify_proof(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    lend_request_id: u64,
+    proof: Binary,
+    public_inputs: Vec<Binary>,
+) -> Result<Response, ContractError> {
+    let mut lend_request = LEND_REQUESTS.load(deps.storage, lend_request_id)
+        .map_err(|_| ContractError::RequestNotFound {})?;
+    if lend_request.borrower != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if lend_request.status != LentStatus::Active {
+        return Err(ContractError::RequestNotActive {});
+    }
+    let lender_info = ORGANIZATIONS.may_load(deps.storage, &lend_request.lender)?
+        .unwrap_or(OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::zero(),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        });
+
+    // The borrower may prove more than the minimum; the threshold it claims is the last input
+    let config = CONFIG.load(deps.storage)?;
+    let public_inputs: Vec<Vec<u8>> = public_inputs.into_iter().map(|input| input.to_vec()).collect();
+    let threshold = public_inputs.last()
+        .and_then(|input| decode_u64(input).ok())
+        .map(Uint128::from)
+        .ok_or(ContractError::ProofInputsMismatch {})?;
+    if threshold < config.min_eligibility_score {
+        return Err(ContractError::BorrowerNotEligible {});
+    }
+    // The rest of the statement is built here, so a proof over any other figures or lender fails
+    let statement = eligibility_statement(deps.storage, &lend_request.borrower, lender_info.carbon_credits, lender_info.debt, threshold)?;
+    if public_inputs != statement {
+        return Err(ContractError::ProofInputsMismatch {});
+    }
+    check_eligibility_proof(deps.storage, &lend_request.borrower, &proof, &statement)?;
+
+    lend_request.eligibility_score = threshold;
+    lend_request.proof_data = hex::encode(proof.as_slice());
+    LEND_REQUESTS.save(deps.storage, lend_request_id, &lend_request)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "verify_proof")
+        .add_attribute("request_id", lend_request_id.to_string())
+        .add_attribute("borrower", info.sender)
+        .add_attribute("lender", lend_request.lender)
+        .add_attribute("eligibility_score", threshold))
+}
+
+pub fn execute_update_verifying_key(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    verifying_key: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // Only a key for the eligibility circuit is stored, so verification never meets a malformed one
+    check_verifying_key(verifying_key.as_slice()).map_err(|_| ContractError::InvalidVerifyingKey {})?;
+    VERIFYING_KEY.save(deps.storage, &verifying_key)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_verifying_key")
+        .add_attribute("verifying_key_hash", hex::encode(Sha256::digest(verifying_key.as_slice()))))
+}
+
+pub fn execute_repay_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    loan_id: u64,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let borrower_info = ORGANIZATIONS.may_load(deps.storage, &borrower)?
+    let mut loan = LEND_REQUESTS.may_load(deps.storage, loan_id)?
+        .ok_or(ContractError::RequestNotFound {})?;
+    if loan.borrower != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if loan.status != LentStatus::Approved && loan.status != LentStatus::Defaulted {
+        return Err(ContractError::RequestNotActive {});
+    }
+    let mut borrower_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
         .unwrap_or(OrganizationInfo {
             reputation_score: Uint128::zero(),
             carbon_credits: Uint128::zero(),
@@ -398,8 +2463,153 @@ pub fn execute_verify_eligibility(
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
-    let lender_info = ORGANIZATIONS.may_load(deps.storage, &lender)?
+    
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    if borrower_info.carbon_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    // Interest stops accruing once a loan is marked defaulted
+    if loan.status == LentStatus::Approved {
+        borrower_info.interest_owed += accrue_interest(&mut loan, env.block.time.seconds());
+    }
+    if amount > loan.outstanding_principal + loan.accrued_interest {
+        return Err(ContractError::RepaymentExceedsDebt {});
+    }
+    let (interest_paid, principal_paid) = settle_loan(deps.storage, loan_id, &mut loan, &mut borrower_info, amount)?;
+    // Collateral is released once the loan is fully repaid
+    let release = match loan.status {
+        LentStatus::Repaid => loan.collateral.take().map(|coin| BankMsg::Send {
+            to_address: loan.borrower.to_string(),
+            amount: vec![coin],
+        }),
+        _ => None,
+    };
+    if release.is_some() {
+        LEND_REQUESTS.save(deps.storage, loan_id, &loan)?;
+    }
+
+    borrower_info.carbon_credits -= amount;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &borrower_info)?;
+    let batches = take_credit_batches(deps.storage, &info.sender, amount)?;
+    pay_lender(deps.storage, &loan, principal_paid, amount, &batches)?;
+
+    
+    Ok(Response::new()
+        .add_messages(release)
+        .add_attribute("method", "repay_tokens")
+        .add_attribute("loan_id", loan_id.to_string())
+        .add_attribute("borrower", info.sender)
+        .add_attribute("lender", loan.lender)
+        .add_attribute("amount", amount)
+        .add_attribute("principal", principal_paid)
+        .add_attribute("interest", interest_paid)
+        .add_attribute("status", format!("{:?}", loan.status)))
+}
+
+/// Applies a repayment to a loan, interest before principal, and to the
+/// borrower's totals. Returns the interest and principal paid.
+fn settle_loan(
+    storage: &mut dyn cosmwasm_std::Storage,
+    loan_id: u64,
+    loan: &mut LendRequest,
+    borrower_info: &mut OrganizationInfo,
+    amount: Uint128,
+) -> StdResult<(Uint128, Uint128)> {
+    let interest_paid = amount.min(loan.accrued_interest);
+    let principal_paid = amount - interest_paid;
+    loan.accrued_interest -= interest_paid;
+    loan.outstanding_principal -= principal_paid;
+    if loan.outstanding_principal.is_zero() && loan.accrued_interest.is_zero() {
+        loan.status = LentStatus::Repaid;
+        DEFAULTED_LOANS.remove(storage, (&loan.borrower, loan_id));
+    }
+    LEND_REQUESTS.save(storage, loan_id, loan)?;
+
+    borrower_info.debt = borrower_info.debt.saturating_sub(principal_paid);
+    borrower_info.total_returned += principal_paid;
+    borrower_info.interest_owed = borrower_info.interest_owed.saturating_sub(interest_paid);
+    borrower_info.total_interest_paid += interest_paid;
+    Ok((interest_paid, principal_paid))
+}
+
+pub fn execute_mark_default(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    loan_id: u64,
+) -> Result<Response, ContractError> {
+    let mut loan = LEND_REQUESTS.may_load(deps.storage, loan_id)?
+        .ok_or(ContractError::RequestNotFound {})?;
+    if loan.status != LentStatus::Approved {
+        return Err(ContractError::RequestNotActive {});
+    }
+    // The lender may call a default at maturity, anyone else once the grace period has passed
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let maturity = loan.maturity.unwrap_or_default();
+    let overdue_from = if info.sender == loan.lender { maturity } else { maturity + config.loan_grace_period };
+    if now <= overdue_from {
+        return Err(ContractError::LoanNotOverdue {});
+    }
+
+    let mut borrower_info = ORGANIZATIONS.load(deps.storage, &loan.borrower)?;
+    borrower_info.interest_owed += accrue_interest(&mut loan, now);
+    let penalty = config.default_penalty.min(borrower_info.reputation_score);
+    borrower_info.reputation_score -= penalty;
+    ORGANIZATIONS.save(deps.storage, &loan.borrower, &borrower_info)?;
+
+    // Pool lenders share the loss of a defaulted loan's principal
+    if let Some(pool_id) = loan.pool_id {
+        let mut pool = POOLS.load(deps.storage, pool_id)?;
+        pool.borrowed = pool.borrowed.saturating_sub(loan.outstanding_principal);
+        POOLS.save(deps.storage, pool_id, &pool)?;
+    }
+    loan.status = LentStatus::Defaulted;
+    loan.defaulted_at = Some(now);
+    // The contract lends for its pools, so their forfeits go to the owner running them
+    let forfeit_to = if loan.pool_id.is_some() { config.owner.to_string() } else { loan.lender.to_string() };
+    let forfeited = loan.collateral.take().map(|coin| BankMsg::Send {
+        to_address: forfeit_to,
+        amount: vec![coin],
+    });
+    LEND_REQUESTS.save(deps.storage, loan_id, &loan)?;
+    DEFAULTED_LOANS.save(deps.storage, (&loan.borrower, loan_id), &cosmwasm_std::Empty {})?;
+
+    Ok(Response::new()
+        .add_messages(forfeited)
+        .add_attribute("method", "mark_default")
+        .add_attribute("loan_id", loan_id.to_string())
+        .add_attribute("borrower", loan.borrower)
+        .add_attribute("lender", loan.lender)
+        .add_attribute("outstanding", loan.outstanding_principal + loan.accrued_interest)
+        .add_attribute("reputation_penalty", penalty))
+}
+
+/// Credits a repayment to the loan's lender, or back into its pool where it
+/// raises the value of every share.
+fn pay_lender(
+    storage: &mut dyn cosmwasm_std::Storage,
+    loan: &LendRequest,
+    principal: Uint128,
+    amount: Uint128,
+    batches: &[CreditBatch],
+) -> StdResult<()> {
+    if let Some(pool_id) = loan.pool_id {
+        let mut pool = POOLS.load(storage, pool_id)?;
+        pool.liquidity += amount;
+        merge_batches(&mut pool.batches, batches);
+        // Principal of a defaulted loan was already written off
+        if loan.defaulted_at.is_none() {
+            pool.borrowed -= principal;
+        }
+        return POOLS.save(storage, pool_id, &pool);
+    }
+    let mut lender_info = ORGANIZATIONS.may_load(storage, &loan.lender)?
         .unwrap_or(OrganizationInfo {
             reputation_score: Uint128::zero(),
             carbon_credits: Uint128::zero(),
@@ -409,64 +2619,199 @@ pub fn execute_verify_eligibility(
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
-    let borrower_emissions = u32::try_from(borrower_info.emissions.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for emissions")))?;
-    let borrower_returned = u32::try_from(borrower_info.total_returned.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for total_returned")))?;
-    let borrower_total_borrowed = u32::try_from(borrower_info.total_borrowed.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for total_borrowed")))?;
-    let borrower_debt = u32::try_from(borrower_info.debt.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for debt")))?;
-    let borrower_credits = u32::try_from(borrower_info.carbon_credits.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for carbon_credits")))?;
-    let borrower_reputation = u32::try_from(borrower_info.reputation_score.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for reputation_score")))?;
-    let lender_credits = u32::try_from(lender_info.carbon_credits.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for lender carbon_credits")))?;
-    let lender_debt = u32::try_from(lender_info.debt.u128())
-        .map_err(|_| ContractError::Std(StdError::generic_err("Conversion error for lender debt")))?;
-    
-    // Call the eligibility_proof function to get score and proof
-    let (eligibility_score, proof_data) = eligibility_proof(
-        borrower_emissions,
-        borrower_returned,
-        borrower_total_borrowed,
-        borrower_debt,
-        borrower_credits,
-        borrower_reputation,
-        lender_credits,
-        lender_debt,
-    );
-    
-    if eligibility_score <= 0 {
-        return Err(ContractError::BorrowerNotEligible {});
+    lender_info.carbon_credits += amount;
+    ORGANIZATIONS.save(storage, &loan.lender, &lender_info)?;
+    add_credit_batches(storage, &loan.lender, batches)
+}
+
+/// Redirects credits newly issued to a borrower to the lenders of its defaulted
+/// loans, oldest loan first, until those loans are cleared.
+fn seize_issuance(
+    storage: &mut dyn cosmwasm_std::Storage,
+    borrower: &Addr,
+    borrower_info: &mut OrganizationInfo,
+    batch: &CreditBatch,
+) -> StdResult<Vec<(u64, Addr, Uint128)>> {
+    let mut available = batch.amount;
+    let mut seizures = vec![];
+    let loan_ids = DEFAULTED_LOANS
+        .prefix(borrower)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for loan_id in loan_ids {
+        if available.is_zero() {
+            break;
+        }
+        let mut loan = LEND_REQUESTS.load(storage, loan_id)?;
+        let seized = available.min(loan.outstanding_principal + loan.accrued_interest);
+        let (_, principal) = settle_loan(storage, loan_id, &mut loan, borrower_info, seized)?;
+        available -= seized;
+        pay_lender(storage, &loan, principal, seized, &[CreditBatch { amount: seized, ..batch.clone() }])?;
+        seizures.push((loan_id, loan.lender, seized));
     }
-    
-    let proof_hex = hex::encode(&proof_data);
-    
-    PROOFS.save(deps.storage, (&borrower, &lender), &proof_data)?;
+    Ok(seizures)
+}
+
+/// Accrues simple interest on the loan's outstanding principal up to `now` and
+/// returns the newly accrued amount.
+pub fn accrue_interest(loan: &mut LendRequest, now: u64) -> Uint128 {
+    let elapsed = now.saturating_sub(loan.accrued_at);
+    let rate = loan.interest_rate.unwrap_or_default() * Decimal::from_ratio(elapsed, SECONDS_PER_YEAR);
+    let interest = loan.outstanding_principal * rate;
+    loan.accrued_interest += interest;
+    loan.accrued_at = now;
+    interest
+}
+
+
+pub fn execute_create_pool(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    min_eligibility_score: Uint128,
+    base_rate: Decimal,
+    rate_slope: Decimal,
+    term: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if term == 0 || term > config.max_loan_term {
+        return Err(ContractError::InvalidLoanTerm { max: config.max_loan_term });
+    }
+    let id = POOL_COUNTER.load(deps.storage)?;
+    POOL_COUNTER.save(deps.storage, &(id + 1))?;
+    let pool = Pool {
+        id,
+        min_eligibility_score,
+        base_rate,
+        rate_slope,
+        term,
+        liquidity: Uint128::zero(),
+        borrowed: Uint128::zero(),
+        total_shares: Uint128::zero(),
+        batches: vec![],
+    };
+    POOLS.save(deps.storage, id, &pool)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "create_pool")
+        .add_attribute("pool_id", id.to_string())
+        .add_attribute("min_eligibility_score", min_eligibility_score)
+        .add_attribute("base_rate", base_rate.to_string())
+        .add_attribute("rate_slope", rate_slope.to_string())
+        .add_attribute("term", term.to_string()))
+}
+
+pub fn execute_deposit_to_pool(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pool_id: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut pool = POOLS.may_load(deps.storage, pool_id)?
+        .ok_or(ContractError::PoolNotFound {})?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    // Shares are priced at the pool's value, principal lent out included
+    let value = pool.liquidity + pool.borrowed;
+    let shares = if pool.total_shares.is_zero() {
+        amount
+    } else if value.is_zero() {
+        return Err(ContractError::PoolWrittenOff {});
+    } else {
+        amount.multiply_ratio(pool.total_shares, value)
+    };
+    let mut org_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotEnoughCredits {})?;
+    if org_info.carbon_credits < amount {
+        return Err(ContractError::NotEnoughCredits {});
+    }
+    org_info.carbon_credits -= amount;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+    let batches = take_credit_batches(deps.storage, &info.sender, amount)?;
+    merge_batches(&mut pool.batches, &batches);
 
+    pool.liquidity += amount;
+    pool.total_shares += shares;
+    POOLS.save(deps.storage, pool_id, &pool)?;
+    POOL_SHARES.update(deps.storage, (pool_id, &info.sender), |held| -> StdResult<_> {
+        Ok(held.unwrap_or_default() + shares)
+    })?;
 
     Ok(Response::new()
-        .add_attribute("method", "verify_eligibility")
-        .add_attribute("borrower", borrower)
-        .add_attribute("lender", lender)
+        .add_attribute("method", "deposit_to_pool")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("depositor", info.sender)
         .add_attribute("amount", amount)
-        .add_attribute("eligibility_score", eligibility_score.to_string())
-        .add_attribute("proof_hex", proof_hex))
+        .add_attribute("shares", shares))
 }
 
+pub fn execute_withdraw_from_pool(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pool_id: u64,
+    shares: Uint128,
+) -> Result<Response, ContractError> {
+    let mut pool = POOLS.may_load(deps.storage, pool_id)?
+        .ok_or(ContractError::PoolNotFound {})?;
+    let held = POOL_SHARES.may_load(deps.storage, (pool_id, &info.sender))?.unwrap_or_default();
+    if shares.is_zero() || shares > held {
+        return Err(ContractError::InsufficientShares {});
+    }
+    let amount = shares.multiply_ratio(pool.liquidity + pool.borrowed, pool.total_shares);
+    if amount > pool.liquidity {
+        return Err(ContractError::InsufficientPoolLiquidity {});
+    }
+    pool.liquidity -= amount;
+    pool.total_shares -= shares;
+    let batches = split_batches(&mut pool.batches, amount);
+    POOLS.save(deps.storage, pool_id, &pool)?;
+    if held == shares {
+        POOL_SHARES.remove(deps.storage, (pool_id, &info.sender));
+    } else {
+        POOL_SHARES.save(deps.storage, (pool_id, &info.sender), &(held - shares))?;
+    }
 
+    let mut org_info = ORGANIZATIONS.load(deps.storage, &info.sender)?;
+    org_info.carbon_credits += amount;
+    ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
+    add_credit_batches(deps.storage, &info.sender, &batches)?;
 
 
-pub fn execute_repay_tokens(
+    Ok(Response::new()
+        .add_attribute("method", "withdraw_from_pool")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("depositor", info.sender)
+        .add_attribute("shares", shares)
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_borrow_from_pool(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    lender: Addr,
+    pool_id: u64,
     amount: Uint128,
+    proof: Binary,
 ) -> Result<Response, ContractError> {
+    let mut pool = POOLS.may_load(deps.storage, pool_id)?
+        .ok_or(ContractError::PoolNotFound {})?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    if amount > pool.liquidity {
+        return Err(ContractError::InsufficientPoolLiquidity {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let collateral = escrow_collateral(&config, &info.funds, amount)?;
     let mut borrower_info = ORGANIZATIONS.may_load(deps.storage, &info.sender)?
         .unwrap_or(OrganizationInfo {
             reputation_score: Uint128::zero(),
@@ -477,44 +2822,78 @@ pub fn execute_repay_tokens(
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
-    
-    let mut lender_info = ORGANIZATIONS.may_load(deps.storage, &lender)?
-        .unwrap_or(OrganizationInfo {
-            reputation_score: Uint128::zero(),
-            carbon_credits: Uint128::zero(),
-            debt: Uint128::zero(),
-            times_borrowed: 0,
-            total_borrowed: Uint128::zero(),
-            total_returned: Uint128::zero(),
-            name: "".to_string(),
-            emissions: Uint128::zero(),
-        });
-    
 
-    if borrower_info.carbon_credits < amount {
-        return Err(ContractError::NotEnoughCredits {});
-    }
-    if borrower_info.debt < amount {
-        return Err(ContractError::NotEnoughCredits {});
-    }
+    // The pool stands in for the lender with the amount drawn and no debt, so deposits and
+    // other draws landing first do not invalidate the proof
+    let statement = eligibility_statement(deps.storage, &info.sender, amount, Uint128::zero(), pool.min_eligibility_score)?;
+    check_eligibility_proof(deps.storage, &info.sender, &proof, &statement)?;
 
-    borrower_info.carbon_credits -= amount;
-    borrower_info.debt -= amount;
-    borrower_info.total_returned += amount;
-    lender_info.carbon_credits += amount;
+    // The loan is priced at the utilization it leaves the pool at
+    pool.liquidity -= amount;
+    pool.borrowed += amount;
+    let batches = split_batches(&mut pool.batches, amount);
+    let interest_rate = pool_rate(&pool);
+    POOLS.save(deps.storage, pool_id, &pool)?;
+
+    let now = env.block.time.seconds();
+    let loan_id = LEND_REQUEST_COUNTER.load(deps.storage)?;
+    LEND_REQUEST_COUNTER.save(deps.storage, &(loan_id + 1))?;
+    let loan = LendRequest {
+        id: loan_id,
+        borrower: info.sender.clone(),
+        lender: env.contract.address.clone(),
+        amount,
+        eligibility_score: pool.min_eligibility_score,
+        proof_data: hex::encode(proof.as_slice()),
+        status: LentStatus::Approved,
+        time: now,
+        interest_rate: Some(interest_rate),
+        term: pool.term,
+        maturity: Some(now + pool.term),
+        outstanding_principal: amount,
+        accrued_interest: Uint128::zero(),
+        accrued_at: now,
+        defaulted_at: None,
+        collateral: collateral.clone(),
+        pool_id: Some(pool_id),
+    };
+    LEND_REQUESTS.save(deps.storage, loan_id, &loan)?;
+
+    borrower_info.carbon_credits += amount;
+    borrower_info.debt += amount;
+    borrower_info.times_borrowed += 1;
+    borrower_info.total_borrowed += amount;
     ORGANIZATIONS.save(deps.storage, &info.sender, &borrower_info)?;
-    ORGANIZATIONS.save(deps.storage, &lender, &lender_info)?;
-    
+    add_credit_batches(deps.storage, &info.sender, &batches)?;
+
+
     Ok(Response::new()
-        .add_attribute("method", "repay_tokens")
+        .add_attribute("method", "borrow_from_pool")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("loan_id", loan_id.to_string())
         .add_attribute("borrower", info.sender)
-        .add_attribute("lender", lender)
-        .add_attribute("amount", amount))
+        .add_attribute("amount", amount)
+        .add_attribute("interest_rate", interest_rate.to_string())
+        .add_attribute("maturity", (now + pool.term).to_string())
+        .add_attribute("collateral", collateral.map(|coin| coin.to_string()).unwrap_or_else(|| "none".to_string())))
+}
+
+fn pool_utilization(pool: &Pool) -> Decimal {
+    let total = pool.liquidity + pool.borrowed;
+    if total.is_zero() {
+        return Decimal::zero();
+    }
+    Decimal::from_ratio(pool.borrowed, total)
 }
 
+// Linear in utilization: base_rate when idle, base_rate + rate_slope when fully lent out
+fn pool_rate(pool: &Pool) -> Decimal {
+    pool.base_rate + pool.rate_slope * pool_utilization(pool)
+}
 
-pub const PROOFS: Map<(&Addr, &Addr), Vec<u8>> = Map::new("proofs");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
@@ -526,9 +2905,37 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetClaims { start_after, limit } => to_binary(&query_claims(deps,_env,start_after, limit)?),
         QueryMsg::GetClaimsByStatus { status, start_after, limit } => to_binary(&query_claims_by_status(deps, _env,status, start_after, limit)?),
         QueryMsg::GetAllOrganizations { start_after, limit } => to_binary(&query_all_organizations(deps, start_after, limit)?),
+        QueryMsg::GetVerifier { address } => to_binary(&query_verifier(deps, address)?),
+        QueryMsg::GetVerifiers { start_after, limit } => to_binary(&query_verifiers(deps, start_after, limit)?),
+        QueryMsg::GetVotingRecord { address, start_after, limit } => to_binary(&query_voting_record(deps, address, start_after, limit)?),
+        QueryMsg::GetDispute { claim_id } => to_binary(&query_dispute(deps, claim_id)?),
+        QueryMsg::GetConflictingClaims { longitudes, latitudes, time_started, time_ended } => {
+            to_binary(&query_conflicting_claims(deps, longitudes, latitudes, time_started, time_ended)?)
+        },
+        QueryMsg::GetAllowance { owner, spender } => to_binary(&query_allowance(deps, owner, spender)?),
+        QueryMsg::GetAllowances { owner, start_after, limit } => to_binary(&query_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::GetCreditBatches { organization, start_after, limit } => {
+            to_binary(&query_credit_batches(deps, organization, start_after, limit)?)
+        },
+        QueryMsg::GetRetirement { id } => to_binary(&RETIREMENTS.load(deps.storage, id)?),
+        QueryMsg::GetOrder { id } => to_binary(&MARKET_ORDERS.load(deps.storage, id)?),
+        QueryMsg::GetPool { pool_id } => to_binary(&pool_response(POOLS.load(deps.storage, pool_id)?)),
+        QueryMsg::GetPools { start_after, limit } => to_binary(&query_pools(deps, start_after, limit)?),
+        QueryMsg::GetPoolShares { pool_id, address } => to_binary(&query_pool_shares(deps, pool_id, address)?),
+        QueryMsg::GetOrderBook { denom, side, start_after, limit } => {
+            to_binary(&query_order_book(deps, denom, side, start_after, limit)?)
+        },
+        QueryMsg::GetRetirementsByOrganization { organization, start_after, limit } => {
+            to_binary(&query_retirements_by_organization(deps, organization, start_after, limit)?)
+        },
+        QueryMsg::GetMethodology { id } => to_binary(&query_methodology(deps, id)?),
+        QueryMsg::GetMethodologies { start_after, limit } => to_binary(&query_methodologies(deps, start_after, limit)?),
+        QueryMsg::PreviewClaimCap { methodology, longitudes, latitudes, time_started, time_ended } => {
+            to_binary(&query_preview_claim_cap(deps, methodology, longitudes, latitudes, time_started, time_ended)?)
+        },
         QueryMsg::UserLendRequests { user, start_after, limit } => {
             let user_addr = deps.api.addr_validate(&user)?;
-            let response = query_user_lend_requests(deps, user_addr, start_after, limit)?;
+            let response = query_user_lend_requests(deps, _env, user_addr, start_after, limit)?;
             to_binary(&response)
         }
     }
@@ -541,6 +2948,31 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         owner: config.owner,
         voting_period: config.voting_period,
         total_carbon_credits: config.total_carbon_credits,
+        min_voters: config.min_voters,
+        quorum: config.quorum,
+        threshold: config.threshold,
+        voting_power: config.voting_power,
+        verifier_min_reputation: config.verifier_min_reputation,
+        verifier_min_bond: config.verifier_min_bond,
+        vote_reward: config.vote_reward,
+        vote_slash: config.vote_slash,
+        bond_slash: config.bond_slash,
+        commit_reveal: config.commit_reveal,
+        reveal_period: config.reveal_period,
+        unrevealed_slash: config.unrevealed_slash,
+        challenge_period: config.challenge_period,
+        dispute_bond: config.dispute_bond,
+        dispute_min_voters: config.dispute_min_voters,
+        dispute_quorum: config.dispute_quorum,
+        max_claim_period: config.max_claim_period,
+        credit_token: config.credit_token,
+        max_loan_term: config.max_loan_term,
+        loan_grace_period: config.loan_grace_period,
+        default_penalty: config.default_penalty,
+        seize_defaulted_issuance: config.seize_defaulted_issuance,
+        min_eligibility_score: config.min_eligibility_score,
+        collateral: config.collateral,
+        quote_denoms: config.quote_denoms,
     })
 }
 
@@ -548,10 +2980,10 @@ fn query_claim(deps: Deps, env: Env, id: u64) -> StdResult<ClaimResponse> {
     let claim = CLAIMS.load(deps.storage, id)?;
     
     // Convert u64 to Timestamp for comparison
-    let voting_end_timestamp = Timestamp::from_seconds(claim.voting_end_time);
+    let reveal_end_timestamp = Timestamp::from_seconds(claim.reveal_end_time);
     
     // Check if voting has ended using env.block.time
-    let (yes_votes, no_votes) = if env.block.time >= voting_end_timestamp {
+    let (yes_votes, no_votes) = if env.block.time >= reveal_end_timestamp {
         (claim.yes_votes, claim.no_votes)
     } else {
         (Uint128::zero(), Uint128::zero())
@@ -566,10 +2998,15 @@ fn query_claim(deps: Deps, env: Env, id: u64) -> StdResult<ClaimResponse> {
         time_ended: claim.time_ended,
         demanded_tokens: claim.demanded_tokens,
         ipfs_hashes: claim.ipfs_hashes,
+        methodology: claim.methodology,
+        polygon: claim.polygon,
         status: claim.status,
         voting_end_time: claim.voting_end_time,
+        commit_reveal: claim.commit_reveal,
+        reveal_end_time: claim.reveal_end_time,
         yes_votes,
         no_votes,
+        finalized_at: claim.finalized_at,
     })
 }
 
@@ -584,6 +3021,8 @@ fn query_organization(deps: Deps, address: Addr) -> StdResult<OrganizationRespon
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
     
     Ok(OrganizationResponse {
@@ -596,6 +3035,8 @@ fn query_organization(deps: Deps, address: Addr) -> StdResult<OrganizationRespon
         total_returned: org_info.total_returned,
         name: org_info.name,
         emissions: org_info.emissions,
+        interest_owed: org_info.interest_owed,
+        total_interest_paid: org_info.total_interest_paid,
     })
 }
 
@@ -608,15 +3049,15 @@ fn query_total_carbon_credits(deps: Deps) -> StdResult<TotalCarbonCreditsRespons
 
 fn query_claims(deps: Deps, env: Env, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ClaimsResponse> {
     let limit = limit.unwrap_or(30) as usize;
-    let start = start_after.map(|s| Bound::exclusive(s));
+    let start = start_after.map(Bound::exclusive);
     
     let claims: Vec<ClaimResponse> = CLAIMS
         .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
         .take(limit)
         .map(|item| {
             let (_, claim) = item?;
-            let voting_end_timestamp = Timestamp::from_seconds(claim.voting_end_time);
-            let (yes_votes, no_votes) = if env.block.time >= voting_end_timestamp {
+            let reveal_end_timestamp = Timestamp::from_seconds(claim.reveal_end_time);
+            let (yes_votes, no_votes) = if env.block.time >= reveal_end_timestamp {
                 (claim.yes_votes, claim.no_votes)
             } else {
                 (Uint128::zero(), Uint128::zero())
@@ -631,10 +3072,15 @@ fn query_claims(deps: Deps, env: Env, start_after: Option<u64>, limit: Option<u3
                 time_ended: claim.time_ended,
                 demanded_tokens: claim.demanded_tokens,
                 ipfs_hashes: claim.ipfs_hashes,
+                methodology: claim.methodology,
+                polygon: claim.polygon,
                 status: claim.status,
                 voting_end_time: claim.voting_end_time,
+                commit_reveal: claim.commit_reveal,
+                reveal_end_time: claim.reveal_end_time,
                 yes_votes,
                 no_votes,
+                finalized_at: claim.finalized_at,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
@@ -644,7 +3090,7 @@ fn query_claims(deps: Deps, env: Env, start_after: Option<u64>, limit: Option<u3
 
 fn query_claims_by_status(deps: Deps, env: Env, status: ClaimStatus, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ClaimsResponse> {
     let limit = limit.unwrap_or(30) as usize;
-    let start = start_after.map(|s| Bound::exclusive(s));
+    let start = start_after.map(Bound::exclusive);
     
     let claims: Vec<ClaimResponse> = CLAIMS
         .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
@@ -657,8 +3103,8 @@ fn query_claims_by_status(deps: Deps, env: Env, status: ClaimStatus, start_after
         .take(limit)
         .map(|item| {
             let (_, claim) = item?;
-            let voting_end_timestamp = Timestamp::from_seconds(claim.voting_end_time);
-            let (yes_votes, no_votes) = if env.block.time >= voting_end_timestamp {
+            let reveal_end_timestamp = Timestamp::from_seconds(claim.reveal_end_time);
+            let (yes_votes, no_votes) = if env.block.time >= reveal_end_timestamp {
                 (claim.yes_votes, claim.no_votes)
             } else {
                 (Uint128::zero(), Uint128::zero())
@@ -673,10 +3119,15 @@ fn query_claims_by_status(deps: Deps, env: Env, status: ClaimStatus, start_after
                 time_ended: claim.time_ended,
                 demanded_tokens: claim.demanded_tokens,
                 ipfs_hashes: claim.ipfs_hashes,
+                methodology: claim.methodology,
+                polygon: claim.polygon,
                 status: claim.status,
                 voting_end_time: claim.voting_end_time,
+                commit_reveal: claim.commit_reveal,
+                reveal_end_time: claim.reveal_end_time,
                 yes_votes,
                 no_votes,
+                finalized_at: claim.finalized_at,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
@@ -699,6 +3150,8 @@ pub fn execute_update_organization_name(
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
     org_info.name = name.clone();
     ORGANIZATIONS.save(deps.storage, &info.sender, &org_info)?;
@@ -726,6 +3179,8 @@ pub fn add_organization_emission(
             total_returned: Uint128::zero(),
             name: "".to_string(),
             emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
         });
     let new_emissions = Uint128::from_str(&emissions)?;
     org_info.emissions = org_info.emissions.checked_add(new_emissions)?;
@@ -739,6 +3194,7 @@ pub fn add_organization_emission(
 
 pub fn query_user_lend_requests(
     deps: Deps,
+    env: Env,
     user: Addr,
     start_after: Option<u64>, 
     limit: Option<u32>,
@@ -749,7 +3205,7 @@ pub fn query_user_lend_requests(
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .filter_map(|item| {
-            let (id, lend_request) = item.ok()?;
+            let (id, mut lend_request) = item.ok()?;
             let role = if lend_request.borrower == user {
                 Some("borrower".to_string())
             } else if lend_request.lender == user {
@@ -757,6 +3213,9 @@ pub fn query_user_lend_requests(
             } else {
                 None
             };
+            if lend_request.status == LentStatus::Approved {
+                accrue_interest(&mut lend_request, env.block.time.seconds());
+            }
             role.map(|role| LendRequestResponse {
                 id,
                 borrower: lend_request.borrower,
@@ -767,6 +3226,13 @@ pub fn query_user_lend_requests(
                 time: lend_request.time,
                 amount: lend_request.amount,
                 role,
+                interest_rate: lend_request.interest_rate,
+                term: lend_request.term,
+                maturity: lend_request.maturity,
+                outstanding_principal: lend_request.outstanding_principal,
+                accrued_interest: lend_request.accrued_interest,
+                defaulted_at: lend_request.defaulted_at,
+                collateral: lend_request.collateral,
             })
         })
         .collect();
@@ -777,10 +3243,7 @@ pub fn query_user_lend_requests(
 fn query_all_organizations(deps: Deps, start_after: Option<Addr>, limit: Option<u32>) -> StdResult<OrganizationsResponse> {
     let limit = limit.unwrap_or(30) as usize;
     
-    let start = match start_after {
-        Some(addr) => Some(Bound::ExclusiveRaw(addr.to_string().into())),
-        None => None,
-    };
+    let start = start_after.map(|addr| Bound::ExclusiveRaw(addr.to_string().into()));
     
     let organizations: Vec<OrganizationListItem> = ORGANIZATIONS
         .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
@@ -798,4 +3261,252 @@ fn query_all_organizations(deps: Deps, start_after: Option<Addr>, limit: Option<
     
     Ok(OrganizationsResponse { organizations })
     
-}
\ No newline at end of file
+}
+
+fn query_verifier(deps: Deps, address: Addr) -> StdResult<VerifierResponse> {
+    let verifier = VERIFIERS.load(deps.storage, &address)?;
+    Ok(VerifierResponse {
+        address,
+        bonded_credits: verifier.bonded_credits,
+        added_at: verifier.added_at,
+        pending_votes: verifier.pending_votes,
+    })
+}
+
+fn query_verifiers(deps: Deps, start_after: Option<Addr>, limit: Option<u32>) -> StdResult<VerifiersResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(|addr| Bound::ExclusiveRaw(addr.to_string().into()));
+
+    let verifiers: Vec<VerifierResponse> = VERIFIERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, verifier) = item?;
+            Ok(VerifierResponse {
+                address,
+                bonded_credits: verifier.bonded_credits,
+                added_at: verifier.added_at,
+                pending_votes: verifier.pending_votes,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(VerifiersResponse { verifiers })
+}
+
+fn query_voting_record(deps: Deps, address: Addr, start_after: Option<u64>, limit: Option<u32>) -> StdResult<VotingRecordResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let records: Vec<VoteRecord> = VOTE_RECORDS
+        .prefix(&address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(VotingRecordResponse { address, records })
+}
+
+fn query_allowance(deps: Deps, owner: Addr, spender: Addr) -> StdResult<AllowanceResponse> {
+    let allowance = ALLOWANCES.may_load(deps.storage, (&owner, &spender))?
+        .unwrap_or(Allowance { amount: Uint128::zero(), expires: Expiration::Never {} });
+    Ok(AllowanceResponse {
+        owner,
+        spender,
+        amount: allowance.amount,
+        expires: allowance.expires,
+    })
+}
+
+fn query_allowances(deps: Deps, owner: Addr, start_after: Option<Addr>, limit: Option<u32>) -> StdResult<AllowancesResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(|addr| Bound::ExclusiveRaw(addr.to_string().into()));
+
+    let allowances = ALLOWANCES
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (spender, allowance) = item?;
+            Ok(AllowanceResponse {
+                owner: owner.clone(),
+                spender,
+                amount: allowance.amount,
+                expires: allowance.expires,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllowancesResponse { allowances })
+}
+
+fn query_credit_batches(deps: Deps, organization: Addr, start_after: Option<u64>, limit: Option<u32>) -> StdResult<CreditBatchesResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let batches = CREDIT_BATCHES
+        .prefix(&organization)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, batch)| batch))
+        .collect::<StdResult<Vec<_>>>()?;
+    let attributed = CREDIT_BATCHES
+        .prefix(&organization)
+        .range(deps.storage, None, None, Order::Ascending)
+        .try_fold(Uint128::zero(), |total, item| item.map(|(_, batch)| total + batch.amount))?;
+    let balance = ORGANIZATIONS.may_load(deps.storage, &organization)?
+        .map(|org_info| org_info.carbon_credits)
+        .unwrap_or_default();
+
+    Ok(CreditBatchesResponse {
+        organization,
+        batches,
+        unattributed: balance.saturating_sub(attributed),
+    })
+}
+
+fn query_retirements_by_organization(deps: Deps, organization: Addr, start_after: Option<u64>, limit: Option<u32>) -> StdResult<RetirementsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let retirements = ORGANIZATION_RETIREMENTS
+        .prefix(&organization)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| RETIREMENTS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RetirementsResponse { retirements })
+}
+
+fn query_order_book(deps: Deps, denom: String, side: OrderSide, start_after: Option<u64>, limit: Option<u32>) -> StdResult<OrderBookResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after
+        .map(|id| MARKET_ORDERS.load(deps.storage, id).map(|order| Bound::exclusive((book_price(&order), id))))
+        .transpose()?;
+
+    let orders = order_book(&side)
+        .sub_prefix(&denom)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| MARKET_ORDERS.load(deps.storage, key?.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OrderBookResponse { denom, side, orders })
+}
+
+fn pool_response(pool: Pool) -> PoolResponse {
+    PoolResponse {
+        utilization: pool_utilization(&pool),
+        borrow_rate: pool_rate(&pool),
+        id: pool.id,
+        min_eligibility_score: pool.min_eligibility_score,
+        base_rate: pool.base_rate,
+        rate_slope: pool.rate_slope,
+        term: pool.term,
+        liquidity: pool.liquidity,
+        borrowed: pool.borrowed,
+        total_shares: pool.total_shares,
+    }
+}
+
+fn query_pools(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<PoolsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let pools = POOLS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, pool)| pool_response(pool)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PoolsResponse { pools })
+}
+
+fn query_pool_shares(deps: Deps, pool_id: u64, address: Addr) -> StdResult<PoolSharesResponse> {
+    let pool = POOLS.load(deps.storage, pool_id)?;
+    let shares = POOL_SHARES.may_load(deps.storage, (pool_id, &address))?.unwrap_or_default();
+    let value = if pool.total_shares.is_zero() {
+        Uint128::zero()
+    } else {
+        shares.multiply_ratio(pool.liquidity + pool.borrowed, pool.total_shares)
+    };
+    Ok(PoolSharesResponse { pool_id, address, shares, value })
+}
+
+fn query_dispute(deps: Deps, claim_id: u64) -> StdResult<DisputeResponse> {
+    let dispute = DISPUTES.load(deps.storage, claim_id)?;
+    Ok(DisputeResponse {
+        claim_id: dispute.claim_id,
+        disputer: dispute.disputer,
+        bond: dispute.bond,
+        original_status: dispute.original_status,
+        opened_at: dispute.opened_at,
+        status: dispute.status,
+    })
+}
+
+fn query_conflicting_claims(
+    deps: Deps,
+    longitudes: Vec<String>,
+    latitudes: Vec<String>,
+    time_started: u64,
+    time_ended: u64,
+) -> StdResult<ConflictingClaimsResponse> {
+    let claim_ids = validate_polygon(&longitudes, &latitudes)
+        .and_then(|polygon| conflicting_claims(deps, &polygon, time_started, time_ended))
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(ConflictingClaimsResponse { claim_ids })
+}
+
+fn query_methodology(deps: Deps, id: String) -> StdResult<MethodologyResponse> {
+    let methodology = METHODOLOGIES.load(deps.storage, &id)?;
+    Ok(MethodologyResponse {
+        id: methodology.id,
+        land_type: methodology.land_type,
+        credits_per_hectare_year: methodology.credits_per_hectare_year,
+    })
+}
+
+fn query_methodologies(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<MethodologiesResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(|id| Bound::ExclusiveRaw(id.into_bytes()));
+    let methodologies = METHODOLOGIES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, methodology) = item?;
+            Ok(MethodologyResponse {
+                id: methodology.id,
+                land_type: methodology.land_type,
+                credits_per_hectare_year: methodology.credits_per_hectare_year,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(MethodologiesResponse { methodologies })
+}
+
+fn query_preview_claim_cap(
+    deps: Deps,
+    methodology: String,
+    longitudes: Vec<String>,
+    latitudes: Vec<String>,
+    time_started: u64,
+    time_ended: u64,
+) -> StdResult<ClaimCapResponse> {
+    let rate = METHODOLOGIES.may_load(deps.storage, &methodology)?
+        .ok_or_else(|| StdError::generic_err(ContractError::MethodologyNotFound {}.to_string()))?;
+    if time_started >= time_ended {
+        return Err(StdError::generic_err(ContractError::InvalidClaimPeriod {}.to_string()));
+    }
+    let polygon = validate_polygon(&longitudes, &latitudes)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let period = time_ended - time_started;
+    Ok(ClaimCapResponse {
+        max_tokens: claim_cap(&rate, polygon.area, period),
+        methodology,
+        area: polygon.area,
+        period,
+    })
+}
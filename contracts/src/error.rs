@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 use cosmwasm_std::OverflowError;
 
@@ -22,6 +22,72 @@ pub enum ContractError {
     #[error("Claim not found")]
     ClaimNotFound {},
 
+    #[error("Claim is not active")]
+    ClaimNotActive {},
+
+    #[error("Only approved or rejected claims can be disputed")]
+    ClaimNotDisputable {},
+
+    #[error("Claim has already been disputed")]
+    AlreadyDisputed {},
+
+    #[error("Challenge period has ended")]
+    ChallengePeriodEnded {},
+
+    #[error("Invalid coordinate: {value}")]
+    InvalidCoordinate { value: String },
+
+    #[error("Longitude must be between -180 and 180")]
+    LongitudeOutOfRange {},
+
+    #[error("Latitude must be between -90 and 90")]
+    LatitudeOutOfRange {},
+
+    #[error("Longitudes and latitudes must have the same length")]
+    CoordinateCountMismatch {},
+
+    #[error("Polygon must start and end at the same coordinate")]
+    PolygonNotClosed {},
+
+    #[error("Polygon needs at least 3 distinct vertices")]
+    TooFewVertices {},
+
+    #[error("Polygon can have at most {max} vertices")]
+    TooManyVertices { max: usize },
+
+    #[error("Polygon edges must not intersect")]
+    SelfIntersectingPolygon {},
+
+    #[error("Polygon encloses no area")]
+    ZeroAreaPolygon {},
+
+    #[error("Claim period must start before it ends")]
+    InvalidClaimPeriod {},
+
+    #[error("Claim period has not ended yet")]
+    ClaimPeriodNotEnded {},
+
+    #[error("Claim period cannot be longer than {max} seconds")]
+    ClaimPeriodTooLong { max: u64 },
+
+    #[error("Claim needs at least one evidence hash")]
+    MissingEvidence {},
+
+    #[error("Invalid IPFS CID: {cid}")]
+    InvalidCid { cid: String },
+
+    #[error("Methodology not found")]
+    MethodologyNotFound {},
+
+    #[error("Demanded tokens exceed the methodology cap of {max}")]
+    DemandExceedsCap { max: Uint128 },
+
+    #[error("Polygon spans too many index tiles")]
+    ClaimAreaTooLarge {},
+
+    #[error("Claim overlaps claim {claim_id} in area and time")]
+    OverlappingClaim { claim_id: u64 },
+
     #[error("Voting period has ended")]
     VotingEnded {},
 
@@ -31,18 +97,156 @@ pub enum ContractError {
     #[error("Already voted")]
     AlreadyVoted {},
 
+    #[error("Votes on this claim must be committed and revealed")]
+    CommitRevealRequired {},
+
+    #[error("Commit-reveal voting is not enabled for this claim")]
+    CommitRevealDisabled {},
+
+    #[error("Reveal period has not started")]
+    RevealNotStarted {},
+
+    #[error("Reveal period has ended")]
+    RevealEnded {},
+
+    #[error("Commitment not found")]
+    CommitmentNotFound {},
+
+    #[error("Revealed vote does not match commitment")]
+    InvalidCommitment {},
+
+    #[error("Sender is not a registered verifier")]
+    NotVerifier {},
+
+    #[error("Verifier already registered")]
+    VerifierExists {},
+
+    #[error("Cannot vote on own claim")]
+    SelfVote {},
+
+    #[error("Verifier bond below minimum")]
+    InsufficientBond {},
+
+    #[error("Bond is locked until the verifier's votes are finalized")]
+    BondLocked {},
+
+    #[error("No voting power")]
+    NoVotingPower {},
+
     #[error("Invalid proof")]
     InvalidProof {},
 
     #[error("Not enough carbon credits")]
     NotEnoughCredits {},
 
+    #[error("Amount must be greater than zero")]
+    InvalidAmount {},
+
+    #[error("Cannot transfer to or approve own account")]
+    CannotSetOwnAccount {},
+
+    #[error("Allowance is expired")]
+    AllowanceExpired {},
+
+    #[error("Allowance is too low")]
+    InsufficientAllowance {},
+
+    #[error("Expiration is already in the past")]
+    InvalidExpiration {},
+
+    #[error("Retirement amount must be greater than zero")]
+    InvalidRetirementAmount {},
+
+    #[error("Order price and amount must be greater than zero")]
+    InvalidOrder {},
+
+    #[error("Orders cannot be priced in {denom}")]
+    UnsupportedDenom { denom: String },
+
+    #[error("Bid must be paid with exactly {expected} of a single denom")]
+    InvalidFunds { expected: Uint128 },
+
+    #[error("Order not found")]
+    OrderNotFound {},
+
+    #[error("Order is not open")]
+    OrderNotOpen {},
+
     #[error("Not enough reputation")]
     NotEnoughReputation {},
 
+    #[error("Voting ratios must be between 0 and 1")]
+    InvalidVotingConfig {},
+
+    #[error("Dispute rounds cannot need fewer voters or a lower quorum than regular votes")]
+    InvalidDisputeConfig {},
+
     #[error("Borrower not eligible")]
     BorrowerNotEligible {},
 
+    #[error("No proof verifying key has been set")]
+    VerifyingKeyNotSet {},
+
+    #[error("Verifying key is not a key for the eligibility circuit")]
+    InvalidVerifyingKey {},
+
+    #[error("Public inputs do not match the attested eligibility commitment and loan terms")]
+    ProofInputsMismatch {},
+
+    #[error("Lend request has no verified eligibility proof")]
+    ProofNotVerified {},
+
+    #[error("Borrower has not committed to its eligibility figures")]
+    CommitmentNotSet {},
+
+    #[error("Eligibility commitment must be an encoded field element")]
+    InvalidEligibilityCommitment {},
+
+    #[error("Eligibility commitment has not been attested")]
+    CommitmentNotAttested {},
+
+    #[error("Commitment does not match the one the borrower posted")]
+    CommitmentMismatch {},
+
+    #[error("Loan term must be greater than zero and at most {max} seconds")]
+    InvalidLoanTerm { max: u64 },
+
+    #[error("Loan is not overdue")]
+    LoanNotOverdue {},
+
+    #[error("Lend request must be collateralized with at least {required}")]
+    InsufficientCollateral { required: String },
+
+    #[error("Collateral is not enabled, no funds may be sent")]
+    UnexpectedFunds {},
+
+    #[error("Pool not found")]
+    PoolNotFound {},
+
+    #[error("Pool does not have enough liquidity")]
+    InsufficientPoolLiquidity {},
+
+    #[error("Not enough pool shares")]
+    InsufficientShares {},
+
+    #[error("Pool was written off and its shares are worthless, deposits would go to their holders")]
+    PoolWrittenOff {},
+
+    #[error("Interest rate must be set by the borrower or the lender")]
+    MissingInterestRate {},
+
+    #[error("Interest rate was already fixed by the borrower")]
+    InterestRateConflict {},
+
+    #[error("Repayment exceeds the outstanding debt")]
+    RepaymentExceedsDebt {},
+
+    #[error("Carbon credits are not backed by a token")]
+    NoCreditToken {},
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
     #[error("Overflow: {0}")]
     Overflow(#[from] OverflowError),
 }
\ No newline at end of file
@@ -0,0 +1,410 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+/// Coordinates are stored as fixed-point micro-degrees.
+pub const COORDINATE_DECIMALS: u32 = 6;
+pub const COORDINATE_SCALE: i64 = 1_000_000;
+pub const MAX_LONGITUDE: i64 = 180 * COORDINATE_SCALE;
+pub const MAX_LATITUDE: i64 = 90 * COORDINATE_SCALE;
+pub const MAX_POLYGON_VERTICES: usize = 100;
+
+// (pi * 6_371_008.8 m / 180)^2, the area of one square degree at the equator
+const SQUARE_METERS_PER_SQUARE_DEGREE: i128 = 12_364_345_868;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Coordinate {
+    pub longitude: i64, // micro-degrees
+    pub latitude: i64,  // micro-degrees
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Polygon {
+    /// Closed ring in counter-clockwise order, the first vertex is repeated at the end
+    pub vertices: Vec<Coordinate>,
+    /// Enclosed area in square meters
+    pub area: Uint128,
+}
+
+/// Parses a decimal degree string such as "-12.345678" into micro-degrees.
+pub fn parse_coordinate(value: &str) -> Result<i64, ContractError> {
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (unsigned, ""),
+    };
+    if whole.is_empty()
+        || whole.len() > 3
+        || fraction.len() > COORDINATE_DECIMALS as usize
+        || unsigned.ends_with('.')
+        || !whole.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(ContractError::InvalidCoordinate { value: value.to_string() });
+    }
+
+    let whole: i64 = whole.parse().map_err(|_| ContractError::InvalidCoordinate { value: value.to_string() })?;
+    let mut micro = 0i64;
+    for (i, digit) in fraction.bytes().enumerate() {
+        micro += (digit - b'0') as i64 * 10i64.pow(COORDINATE_DECIMALS - 1 - i as u32);
+    }
+    let parsed = whole * COORDINATE_SCALE + micro;
+    Ok(if negative { -parsed } else { parsed })
+}
+
+/// Validates the claimed geodata and returns it as a normalized polygon.
+///
+/// The ring must be explicitly closed (first and last vertex equal), stay within
+/// coordinate bounds, have at least three distinct vertices, not intersect itself
+/// and enclose a non-zero area. Repeated consecutive vertices are dropped and the
+/// ring is reoriented counter-clockwise, keeping its starting vertex. Polygons
+/// crossing the antimeridian are not supported.
+pub fn validate_polygon(longitudes: &[String], latitudes: &[String]) -> Result<Polygon, ContractError> {
+    if longitudes.len() != latitudes.len() {
+        return Err(ContractError::CoordinateCountMismatch {});
+    }
+    if longitudes.len() > MAX_POLYGON_VERTICES + 1 {
+        return Err(ContractError::TooManyVertices { max: MAX_POLYGON_VERTICES });
+    }
+
+    let mut ring = Vec::with_capacity(longitudes.len());
+    for (longitude, latitude) in longitudes.iter().zip(latitudes.iter()) {
+        let longitude = parse_coordinate(longitude)?;
+        let latitude = parse_coordinate(latitude)?;
+        if longitude.abs() > MAX_LONGITUDE {
+            return Err(ContractError::LongitudeOutOfRange {});
+        }
+        if latitude.abs() > MAX_LATITUDE {
+            return Err(ContractError::LatitudeOutOfRange {});
+        }
+        ring.push(Coordinate { longitude, latitude });
+    }
+
+    if ring.len() < 2 || ring.first() != ring.last() {
+        return Err(ContractError::PolygonNotClosed {});
+    }
+    ring.pop();
+    ring.dedup();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    if ring.len() < 3 {
+        return Err(ContractError::TooFewVertices {});
+    }
+    if is_self_intersecting(&ring) {
+        return Err(ContractError::SelfIntersectingPolygon {});
+    }
+
+    let doubled_area = doubled_signed_area(&ring);
+    if doubled_area == 0 {
+        return Err(ContractError::ZeroAreaPolygon {});
+    }
+    if doubled_area < 0 {
+        ring.reverse();
+        ring.rotate_right(1);
+    }
+    let area = (doubled_area.unsigned_abs() * SQUARE_METERS_PER_SQUARE_DEGREE as u128)
+        / (2 * (COORDINATE_SCALE as u128).pow(2));
+
+    ring.push(ring[0]);
+    Ok(Polygon { vertices: ring, area: Uint128::new(area) })
+}
+
+// Shoelace formula over the sinusoidal (equal-area) projection x = lon * cos(lat),
+// in micro-degrees squared.
+fn doubled_signed_area(ring: &[Coordinate]) -> i128 {
+    let projected: Vec<(i128, i128)> = ring
+        .iter()
+        .map(|c| {
+            let (numerator, denominator) = cos_ratio(c.latitude);
+            (c.longitude as i128 * numerator / denominator, c.latitude as i128)
+        })
+        .collect();
+    let mut sum = 0i128;
+    for i in 0..projected.len() {
+        let (x1, y1) = projected[i];
+        let (x2, y2) = projected[(i + 1) % projected.len()];
+        sum += x1 * y2 - x2 * y1;
+    }
+    sum
+}
+
+// Bhaskara's approximation of cos(latitude) as a fraction, within 0.2% over [-90, 90] degrees.
+fn cos_ratio(latitude: i64) -> (i128, i128) {
+    let half_turn = (180 * COORDINATE_SCALE) as i128;
+    let latitude = latitude as i128;
+    (
+        half_turn * half_turn - 4 * latitude * latitude,
+        half_turn * half_turn + latitude * latitude,
+    )
+}
+
+fn is_self_intersecting(ring: &[Coordinate]) -> bool {
+    let n = ring.len();
+    for i in 0..n {
+        let (a1, a2) = (ring[i], ring[(i + 1) % n]);
+        for j in (i + 1)..n {
+            let (b1, b2) = (ring[j], ring[(j + 1) % n]);
+            let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+            if adjacent {
+                // Adjacent edges only share their common vertex unless they fold back onto each other
+                let (shared, a_other, b_other) = if j == i + 1 { (a2, a1, b2) } else { (a1, a2, b1) };
+                if orientation(shared, a_other, b_other) == 0 && same_direction(shared, a_other, b_other) {
+                    return true;
+                }
+                continue;
+            }
+            if segments_intersect(a1, a2, b1, b2) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn orientation(a: Coordinate, b: Coordinate, c: Coordinate) -> i8 {
+    let cross = (b.longitude - a.longitude) as i128 * (c.latitude - a.latitude) as i128
+        - (b.latitude - a.latitude) as i128 * (c.longitude - a.longitude) as i128;
+    cross.signum() as i8
+}
+
+fn same_direction(origin: Coordinate, a: Coordinate, b: Coordinate) -> bool {
+    let dot = (a.longitude - origin.longitude) as i128 * (b.longitude - origin.longitude) as i128
+        + (a.latitude - origin.latitude) as i128 * (b.latitude - origin.latitude) as i128;
+    dot > 0
+}
+
+fn on_segment(a: Coordinate, b: Coordinate, p: Coordinate) -> bool {
+    p.longitude >= a.longitude.min(b.longitude)
+        && p.longitude <= a.longitude.max(b.longitude)
+        && p.latitude >= a.latitude.min(b.latitude)
+        && p.latitude <= a.latitude.max(b.latitude)
+}
+
+pub fn segments_intersect(a1: Coordinate, a2: Coordinate, b1: Coordinate, b2: Coordinate) -> bool {
+    let o1 = orientation(a1, a2, b1);
+    let o2 = orientation(a1, a2, b2);
+    let o3 = orientation(b1, b2, a1);
+    let o4 = orientation(b1, b2, a2);
+    if o1 != o2 && o3 != o4 {
+        return true;
+    }
+    (o1 == 0 && on_segment(a1, a2, b1))
+        || (o2 == 0 && on_segment(a1, a2, b2))
+        || (o3 == 0 && on_segment(b1, b2, a1))
+        || (o4 == 0 && on_segment(b1, b2, a2))
+}
+
+/// Side of the square tiles used to bucket claims in the spatial index, 0.25 degrees.
+pub const TILE_SIZE: i64 = COORDINATE_SCALE / 4;
+pub const MAX_POLYGON_TILES: usize = 256;
+
+/// Returns every tile touched by the polygon's bounding box.
+pub fn covering_tiles(polygon: &Polygon) -> Result<Vec<(i32, i32)>, ContractError> {
+    let min_x = polygon.vertices.iter().map(|c| c.longitude).min().unwrap_or_default().div_euclid(TILE_SIZE);
+    let max_x = polygon.vertices.iter().map(|c| c.longitude).max().unwrap_or_default().div_euclid(TILE_SIZE);
+    let min_y = polygon.vertices.iter().map(|c| c.latitude).min().unwrap_or_default().div_euclid(TILE_SIZE);
+    let max_y = polygon.vertices.iter().map(|c| c.latitude).max().unwrap_or_default().div_euclid(TILE_SIZE);
+    if ((max_x - min_x + 1) * (max_y - min_y + 1)) as usize > MAX_POLYGON_TILES {
+        return Err(ContractError::ClaimAreaTooLarge {});
+    }
+    let mut tiles = vec![];
+    for x in min_x..=max_x {
+        for y in min_y..=max_y {
+            tiles.push((x as i32, y as i32));
+        }
+    }
+    Ok(tiles)
+}
+
+/// Whether the interiors of two polygons overlap. Parcels that only share
+/// boundary edges or corners do not overlap.
+pub fn polygons_overlap(a: &Polygon, b: &Polygon) -> bool {
+    let (a, b) = (&a.vertices, &b.vertices);
+    if a == b {
+        return true;
+    }
+    for edge_a in a.windows(2) {
+        for edge_b in b.windows(2) {
+            if segments_cross(edge_a[0], edge_a[1], edge_b[0], edge_b[1]) {
+                return true;
+            }
+        }
+    }
+    // Without crossing edges one polygon is either disjoint from or nested in
+    // the other, so probing vertices and edge midpoints is enough
+    contains_probe(a, b) || contains_probe(b, a)
+}
+
+fn contains_probe(outer: &[Coordinate], inner: &[Coordinate]) -> bool {
+    // Work in doubled coordinates so edge midpoints stay on the integer grid
+    let doubled = |c: &Coordinate| (2 * c.longitude as i128, 2 * c.latitude as i128);
+    let outer: Vec<(i128, i128)> = outer.iter().map(doubled).collect();
+    let mut probes: Vec<(i128, i128)> = inner.iter().map(doubled).collect();
+    for edge in inner.windows(2) {
+        probes.push((
+            edge[0].longitude as i128 + edge[1].longitude as i128,
+            edge[0].latitude as i128 + edge[1].latitude as i128,
+        ));
+    }
+    // Congruent parcels share every boundary point, so also probe the vertex
+    // average when it lies inside the inner ring
+    let count = (inner.len() - 1) as i128;
+    let (sum_x, sum_y) = inner[..inner.len() - 1]
+        .iter()
+        .fold((0i128, 0i128), |(x, y), c| (x + c.longitude as i128, y + c.latitude as i128));
+    let average = (2 * sum_x / count, 2 * sum_y / count);
+    if strictly_inside(&inner.iter().map(doubled).collect::<Vec<_>>(), average) {
+        probes.push(average);
+    }
+    probes.into_iter().any(|probe| strictly_inside(&outer, probe))
+}
+
+// Ray casting over a closed ring, points on the boundary are not inside.
+fn strictly_inside(ring: &[(i128, i128)], (px, py): (i128, i128)) -> bool {
+    let mut inside = false;
+    for edge in ring.windows(2) {
+        let ((x1, y1), (x2, y2)) = (edge[0], edge[1]);
+        let cross = (x2 - x1) * (py - y1) - (y2 - y1) * (px - x1);
+        if cross == 0 && px >= x1.min(x2) && px <= x1.max(x2) && py >= y1.min(y2) && py <= y1.max(y2) {
+            return false;
+        }
+        if (y1 > py) != (y2 > py) {
+            // Sign of the crossing's x offset relative to the probe, without dividing
+            let upward = y2 > y1;
+            if (cross > 0) == upward {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+fn segments_cross(a1: Coordinate, a2: Coordinate, b1: Coordinate, b2: Coordinate) -> bool {
+    let o1 = orientation(a1, a2, b1);
+    let o2 = orientation(a1, a2, b2);
+    let o3 = orientation(b1, b2, a1);
+    let o4 = orientation(b1, b2, a2);
+    o1 * o2 < 0 && o3 * o4 < 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_parse_to_micro_degrees() {
+        assert_eq!(parse_coordinate("12.345678"), Ok(12_345_678));
+        assert_eq!(parse_coordinate("-12.3"), Ok(-12_300_000));
+        assert_eq!(parse_coordinate("+1.000000"), Ok(COORDINATE_SCALE));
+        assert_eq!(parse_coordinate("180"), Ok(MAX_LONGITUDE));
+        assert_eq!(parse_coordinate("-0"), Ok(0));
+        for value in ["", ".5", "1.", "1.0000001", "1e3", "east", "--1", "1000", " 1"] {
+            assert_eq!(parse_coordinate(value), Err(ContractError::InvalidCoordinate { value: value.to_string() }), "{}", value);
+        }
+    }
+
+    fn parcel(longitudes: &[&str], latitudes: &[&str]) -> Result<Polygon, ContractError> {
+        let longitudes: Vec<String> = longitudes.iter().map(|value| value.to_string()).collect();
+        let latitudes: Vec<String> = latitudes.iter().map(|value| value.to_string()).collect();
+        validate_polygon(&longitudes, &latitudes)
+    }
+
+    fn point(longitude: i64, latitude: i64) -> Coordinate {
+        Coordinate { longitude, latitude }
+    }
+
+    #[test]
+    fn clockwise_polygons_are_reoriented() {
+        let counter_clockwise = parcel(&["0", "1", "1", "0", "0"], &["0", "0", "1", "1", "0"]).unwrap();
+        let clockwise = parcel(&["0", "0", "1", "1", "0"], &["0", "1", "1", "0", "0"]).unwrap();
+        assert_eq!(clockwise, counter_clockwise);
+        assert_eq!(clockwise.vertices[0], point(0, 0));
+
+        // Repeated vertices are dropped before the ring is checked
+        let repeated = parcel(&["0", "1", "1", "1", "0", "0"], &["0", "0", "0", "1", "1", "0"]).unwrap();
+        assert_eq!(repeated, counter_clockwise);
+    }
+
+    #[test]
+    fn malformed_polygons_are_rejected() {
+        assert_eq!(parcel(&["0", "1", "1", "0"], &["0", "0", "1", "1"]).unwrap_err(), ContractError::PolygonNotClosed {});
+        assert_eq!(parcel(&["0", "1", "0"], &["0", "0", "0"]).unwrap_err(), ContractError::TooFewVertices {});
+        assert_eq!(parcel(&["0", "1", "2", "0"], &["0", "0", "0", "0"]).unwrap_err(), ContractError::SelfIntersectingPolygon {});
+        // A bow tie crosses itself in the middle
+        assert_eq!(parcel(&["0", "1", "0", "1", "0"], &["0", "1", "1", "0", "0"]).unwrap_err(), ContractError::SelfIntersectingPolygon {});
+        // A spike that folds back along its own edge
+        assert_eq!(
+            parcel(&["0", "2", "1", "1", "0", "0"], &["0", "0", "0", "1", "1", "0"]).unwrap_err(),
+            ContractError::SelfIntersectingPolygon {}
+        );
+        assert_eq!(parcel(&["0", "1", "2", "1", "0"], &["0", "0", "0", "0", "0"]).unwrap_err(), ContractError::SelfIntersectingPolygon {});
+        assert_eq!(parcel(&["180.000001", "1", "1", "180.000001"], &["0", "0", "1", "0"]).unwrap_err(), ContractError::LongitudeOutOfRange {});
+        assert_eq!(parcel(&["0", "1", "1", "0"], &["0", "0", "90.5", "0"]).unwrap_err(), ContractError::LatitudeOutOfRange {});
+    }
+
+    #[test]
+    fn antimeridian_polygons_are_not_indexed() {
+        // Without antimeridian support the ring wraps around the globe the long way
+        let crossing = parcel(&["179.9", "-179.9", "-179.9", "179.9", "179.9"], &["0", "0", "0.1", "0.1", "0"]).unwrap();
+        assert_eq!(covering_tiles(&crossing).unwrap_err(), ContractError::ClaimAreaTooLarge {});
+    }
+
+    #[test]
+    fn segment_intersections() {
+        // Crossing diagonals
+        assert!(segments_intersect(point(0, 0), point(2, 2), point(0, 2), point(2, 0)));
+        // Touching at an endpoint
+        assert!(segments_intersect(point(0, 0), point(2, 0), point(2, 0), point(2, 2)));
+        assert!(segments_intersect(point(0, 0), point(2, 0), point(1, 0), point(1, 2)));
+        // Collinear segments that overlap or only line up
+        assert!(segments_intersect(point(0, 0), point(2, 0), point(1, 0), point(3, 0)));
+        assert!(!segments_intersect(point(0, 0), point(1, 0), point(2, 0), point(3, 0)));
+        // Parallel and apart
+        assert!(!segments_intersect(point(0, 0), point(2, 0), point(0, 1), point(2, 1)));
+        assert!(!segments_intersect(point(0, 0), point(2, 2), point(3, 0), point(3, 1)));
+    }
+
+    #[test]
+    fn polygon_overlaps() {
+        let square = parcel(&["0", "2", "2", "0", "0"], &["0", "0", "2", "2", "0"]).unwrap();
+        let neighbour = parcel(&["2", "4", "4", "2", "2"], &["0", "0", "2", "2", "0"]).unwrap();
+        let corner = parcel(&["2", "3", "3", "2", "2"], &["2", "2", "3", "3", "2"]).unwrap();
+        let nested = parcel(&["0.5", "1.5", "1.5", "0.5", "0.5"], &["0.5", "0.5", "1.5", "1.5", "0.5"]).unwrap();
+        let crossing = parcel(&["1", "3", "3", "1", "1"], &["1", "1", "3", "3", "1"]).unwrap();
+        let inner_edge = parcel(&["0", "1", "1", "0", "0"], &["0", "0", "1", "1", "0"]).unwrap();
+        let distant = parcel(&["5", "6", "6", "5", "5"], &["5", "5", "6", "6", "5"]).unwrap();
+
+        // Sharing a boundary edge or a corner is not an overlap
+        assert!(!polygons_overlap(&square, &neighbour));
+        assert!(!polygons_overlap(&square, &corner));
+        assert!(!polygons_overlap(&square, &distant));
+        // Nesting is an overlap in either order, including along a shared edge
+        assert!(polygons_overlap(&square, &nested));
+        assert!(polygons_overlap(&nested, &square));
+        assert!(polygons_overlap(&square, &inner_edge));
+        assert!(polygons_overlap(&square, &crossing));
+        assert!(polygons_overlap(&square, &square));
+    }
+
+    #[test]
+    fn covering_tiles_respects_the_limit() {
+        // 16 by 16 tiles of 0.25 degrees is exactly the limit
+        let largest = parcel(&["0", "3.999999", "3.999999", "0", "0"], &["0", "0", "3.999999", "3.999999", "0"]).unwrap();
+        let tiles = covering_tiles(&largest).unwrap();
+        assert_eq!(tiles.len(), MAX_POLYGON_TILES);
+        assert_eq!(tiles.first(), Some(&(0, 0)));
+        assert_eq!(tiles.last(), Some(&(15, 15)));
+        let too_large = parcel(&["0", "4", "4", "0", "0"], &["0", "0", "3.999999", "3.999999", "0"]).unwrap();
+        assert_eq!(covering_tiles(&too_large).unwrap_err(), ContractError::ClaimAreaTooLarge {});
+
+        // Negative coordinates round down to the tile below
+        let small = parcel(&["-0.1", "0.1", "0.1", "-0.1", "-0.1"], &["-0.1", "-0.1", "0.1", "0.1", "-0.1"]).unwrap();
+        assert_eq!(covering_tiles(&small).unwrap(), vec![(-1, -1), (-1, 0), (0, -1), (0, 0)]);
+    }
+}
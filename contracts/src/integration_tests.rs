@@ -1,13 +1,117 @@
 #[cfg(test)]
-
 mod tests {
-    use cosmwasm_std::{Addr, Uint128, testing::{mock_dependencies, mock_env, mock_info}, from_binary};
-    use crate::{contract::{instantiate, execute, query}, msg::{InstantiateMsg, ExecuteMsg, QueryMsg, ConfigResponse, ClaimResponse,OrganizationsResponse,OrganizationResponse, TotalCarbonCreditsResponse, ClaimsResponse}, state::{VoteOption, ClaimStatus}};
-    use cosmwasm_std::coins;
-    use crate::state::ORGANIZATIONS;
-    use crate::contract::add_organization_emission;
+    use cosmwasm_std::{Addr, Decimal, Uint128, testing::{mock_dependencies, mock_env, mock_info}, from_binary};
+    use crate::{contract::{instantiate, execute, query, reply}, msg::{InstantiateMsg, CreditTokenInfo, ExecuteMsg, ReceiveMsg, QueryMsg, ConfigResponse, ClaimResponse,OrganizationsResponse,OrganizationResponse, TotalCarbonCreditsResponse, ClaimsResponse, VerifierResponse, VerifiersResponse, VotingRecordResponse, DisputeResponse, ConflictingClaimsResponse, MethodologiesResponse, ClaimCapResponse, RetirementsResponse, CreditBatchesResponse, AllowanceResponse, AllowancesResponse, OrderBookResponse, PoolResponse, PoolSharesResponse}, state::{VoteOption, VotingPowerModel, ClaimStatus, DisputeStatus}};
+    use cosmwasm_std::{coins, to_binary, BankMsg, Coin, SubMsg};
+    use cw_multi_test::{App, ContractWrapper, Executor};
+    use cw_utils::Expiration;
+    use crate::state::{CollateralConfig, CreditBatch, LendRequestResponse, LentStatus, MarketOrder, UserLendRequestsResponse, Methodology, OrderSide, OrderStatus, RetirementCertificate, CREDIT_BATCHES, ORGANIZATIONS};
+    use crate::contract::{vote_commitment_hash, MAX_ORDER_MATCHES};
     use crate::ContractError;
-    use cosmwasm_std::OverflowError;
+    use crate::cid::{validate_cid, MAX_CID_LENGTH};
+    use crate::geometry::Coordinate;
+    use cosmwasm_std::{Binary, Deps, DepsMut, Env};
+    use crate::state::LEND_REQUESTS;
+    use std::sync::OnceLock;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use zero_knowledge_proofs::{eligibility_proof, generate_keys, BorrowerFigures, EligibilityInputs, ProofError};
+
+    // Groth16 setup is the slow part, so all tests share one pair of keys
+    fn proof_keys() -> &'static (Vec<u8>, Vec<u8>) {
+        static KEYS: OnceLock<(Vec<u8>, Vec<u8>)> = OnceLock::new();
+        KEYS.get_or_init(|| generate_keys(&mut StdRng::seed_from_u64(0)).unwrap())
+    }
+
+    const SALT: [u8; 32] = [1; 32];
+
+    // The configuration tests instantiate with, each overriding only the fields it exercises
+    fn default_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            voting_period: 86400,
+            min_voters: 1,
+            quorum: Decimal::zero(),
+            threshold: Decimal::percent(50),
+            voting_power: VotingPowerModel::OneAddressOneVote,
+            verifier_min_reputation: Uint128::zero(),
+            verifier_min_bond: Uint128::zero(),
+            vote_reward: Uint128::new(1),
+            vote_slash: Uint128::zero(),
+            bond_slash: Decimal::zero(),
+            commit_reveal: false,
+            reveal_period: 0,
+            unrevealed_slash: Uint128::zero(),
+            challenge_period: 86400,
+            dispute_bond: Uint128::zero(),
+            dispute_min_voters: 1,
+            dispute_quorum: Decimal::zero(),
+            max_claim_period: 31536000,
+            max_loan_term: 31536000,
+            loan_grace_period: 0,
+            default_penalty: Uint128::zero(),
+            seize_defaulted_issuance: false,
+            min_eligibility_score: Uint128::zero(),
+            collateral: None,
+            quote_denoms: vec!["inj".to_string()],
+            methodologies: vec![Methodology {
+                id: "forestry".to_string(),
+                land_type: "forest".to_string(),
+                credits_per_hectare_year: Decimal::from_ratio(1_000_000u128, 1u128),
+            }],
+            credit_token: None,
+        }
+    }
+
+    fn organization(deps: Deps, env: &Env, address: &Addr) -> OrganizationResponse {
+        let query_msg = QueryMsg::GetOrganization { address: address.clone() };
+        from_binary(&query(deps, env.clone(), query_msg).unwrap()).unwrap()
+    }
+
+    // Posts a commitment to the organization's recorded figures and has the owner attest it,
+    // returning the figures to prove from
+    fn commit_figures(mut deps: DepsMut, env: &Env, address: &Addr) -> BorrowerFigures {
+        let organization = organization(deps.as_ref(), env, address);
+        let figures = BorrowerFigures {
+            emissions: organization.emissions.u128() as u64,
+            returned: organization.total_returned.u128() as u64,
+            borrowed: organization.total_borrowed.u128() as u64,
+            debt: organization.debt.u128() as u64,
+            credits: organization.carbon_credits.u128() as u64,
+            reputation: organization.reputation_score.u128() as u64,
+        };
+        let commitment = Binary::from(figures.commitment(&SALT));
+        let commit_msg = ExecuteMsg::CommitEligibility { commitment: commitment.clone() };
+        execute(deps.branch(), env.clone(), mock_info(address.as_str(), &[]), commit_msg).unwrap();
+        let attest_msg = ExecuteMsg::AttestEligibility { borrower: address.clone(), commitment };
+        execute(deps, env.clone(), mock_info("creator", &[]), attest_msg).unwrap();
+        figures
+    }
+
+    fn lend_request_inputs(mut deps: DepsMut, env: &Env, lend_request_id: u64) -> EligibilityInputs {
+        let lend_request = LEND_REQUESTS.load(deps.storage, lend_request_id).unwrap();
+        let borrower = commit_figures(deps.branch(), env, &lend_request.borrower);
+        let lender = organization(deps.as_ref(), env, &lend_request.lender);
+        let config: ConfigResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap()).unwrap();
+        EligibilityInputs {
+            borrower,
+            salt: SALT,
+            lender_credits: lender.carbon_credits.u128() as u64,
+            lender_debt: lender.debt.u128() as u64,
+            threshold: config.min_eligibility_score.u128() as u64,
+        }
+    }
+
+    // Sets the verifying key and has the borrower prove the request from the current figures
+    fn prove_lend_request(mut deps: DepsMut, env: &Env, lend_request_id: u64) {
+        let (proving_key, verifying_key) = proof_keys();
+        let update_msg = ExecuteMsg::UpdateVerifyingKey { verifying_key: Binary::from(verifying_key.clone()) };
+        execute(deps.branch(), env.clone(), mock_info("creator", &[]), update_msg).unwrap();
+        let inputs = lend_request_inputs(deps.branch(), env, lend_request_id);
+        let (_, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(0)).unwrap();
+        let public_inputs = inputs.public_inputs().into_iter().map(Binary::from).collect();
+        let borrower = LEND_REQUESTS.load(deps.storage, lend_request_id).unwrap().borrower;
+        let verify_msg = ExecuteMsg::VerifyProof { lend_request_id, proof: Binary::from(proof), public_inputs };
+        execute(deps, env.clone(), mock_info(borrower.as_str(), &[]), verify_msg).unwrap();
+    }
 
     #[test]
     fn proper_initialization() {
@@ -15,9 +119,7 @@ mod tests {
         let env = mock_env();
         let info = mock_info("creator", &[]);
         
-        let msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let msg = default_instantiate_msg();
         
         let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
         assert_eq!(0, res.messages.len());
@@ -33,17 +135,16 @@ mod tests {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info("creator", &[]);
-        let msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         let create_claim_msg = ExecuteMsg::CreateClaim {
-            longitudes: vec!["123.456".to_string()],
-            latitudes: vec!["78.90".to_string()],
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
             time_started: 1000,
             time_ended: 2000,
             demanded_tokens: Uint128::new(100),
-            ipfs_hashes: vec!["QmHash1".to_string()],
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
         };
         
         let res = execute(deps.as_mut(), env.clone(), info, create_claim_msg).unwrap();
@@ -60,23 +161,27 @@ mod tests {
     }
     
     #[test]
+    #[allow(unused_variables)]
     fn voting_works() {
         let mut deps = mock_dependencies();
         let mut env = mock_env();
         let info = mock_info("creator", &[]);
-        let msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for verifier in ["voter1", "voter2", "voter3"] {
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(verifier) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
         
         // Create a claim
         let create_claim_msg = ExecuteMsg::CreateClaim {
-            longitudes: vec!["123.456".to_string()],
-            latitudes: vec!["78.90".to_string()],
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
             time_started: 1000,
             time_ended: 2000,
             demanded_tokens: Uint128::new(100),
-            ipfs_hashes: vec!["QmHash1".to_string()],
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
         };
         
         execute(deps.as_mut(), env.clone(), info, create_claim_msg).unwrap();
@@ -143,17 +248,18 @@ mod tests {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let creator_info = mock_info("creator", &[]);
-        let msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), creator_info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), creator_info.clone(), add_verifier_msg).unwrap();
         let create_claim_msg = ExecuteMsg::CreateClaim {
-            longitudes: vec!["123.456".to_string()],
-            latitudes: vec!["78.90".to_string()],
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
             time_started: 1000,
             time_ended: 2000,
             demanded_tokens: Uint128::new(100),
-            ipfs_hashes: vec!["QmHash1".to_string()],
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
         };
         
         execute(deps.as_mut(), env.clone(), creator_info.clone(), create_claim_msg).unwrap();
@@ -170,9 +276,19 @@ mod tests {
             claim_id: 0,
         };
         execute(deps.as_mut(), env2.clone(), creator_info.clone(), finalize_msg).unwrap();
-        let lend_msg = ExecuteMsg::LendTokens {
-            borrower: Addr::unchecked("borrower"),
+        let request_msg = ExecuteMsg::CreateLendToken {
+            lender: Addr::unchecked("creator"),
             amount: Uint128::new(50),
+            interest_rate: Some(Decimal::percent(10)),
+            term: 31536000,
+            proof: None,
+        };
+        execute(deps.as_mut(), env2.clone(), mock_info("borrower", &[]), request_msg).unwrap();
+        prove_lend_request(deps.as_mut(), &env2, 0);
+        let lend_msg = ExecuteMsg::LendTokens {
+            lend_request_id: 0,
+            response: "accepted".to_string(),
+            interest_rate: None,
         };
         execute(deps.as_mut(), env2.clone(), creator_info.clone(), lend_msg).unwrap();
         let creator_query = QueryMsg::GetOrganization { address: Addr::unchecked("creator") };
@@ -188,7 +304,7 @@ mod tests {
         assert_eq!(borrower_res.total_borrowed, Uint128::new(50));
         let borrower_info = mock_info("borrower", &[]);
         let repay_msg = ExecuteMsg::RepayTokens {
-            lender: Addr::unchecked("creator"),
+            loan_id: 0,
             amount: Uint128::new(30),
         };
         execute(deps.as_mut(), env2.clone(), borrower_info, repay_msg).unwrap();
@@ -212,18 +328,19 @@ mod tests {
         let env = mock_env();
         
         let creator_info = mock_info("creator", &[]);
-        let msg = InstantiateMsg {
-            voting_period: 86400, // 1 day
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), creator_info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), creator_info.clone(), add_verifier_msg).unwrap();
         for i in 0..3 {
             let create_claim_msg = ExecuteMsg::CreateClaim {
-                longitudes: vec![format!("123.{}", i)],
-                latitudes: vec![format!("78.{}", i)],
+                longitudes: vec![format!("123.{}1", i), format!("123.{}2", i), format!("123.{}2", i), format!("123.{}1", i), format!("123.{}1", i)],
+                latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
                 time_started: 1000 + i,
                 time_ended: 2000 + i,
                 demanded_tokens: Uint128::new(100 + i as u128),
-                ipfs_hashes: vec![format!("QmHash{}", i)],
+                ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+                methodology: "forestry".to_string(),
             };
             
             execute(deps.as_mut(), env.clone(), creator_info.clone(), create_claim_msg).unwrap();
@@ -248,7 +365,7 @@ mod tests {
             claim_id: 1,
             vote: VoteOption::Yes,
         };
-        execute(deps.as_mut(), env.clone(), creator_info.clone(), vote_msg).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
         
         let mut env2 = env.clone();
         env2.block.time = env.block.time.plus_seconds(86401);
@@ -279,17 +396,18 @@ mod tests {
         let env = mock_env();
         let info = mock_info("creator", &[]);
 
-        let msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter1") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
         let create_claim_msg = ExecuteMsg::CreateClaim {
-            longitudes: vec!["123.456".to_string()],
-            latitudes: vec!["78.90".to_string()],
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
             time_started: 1000,
             time_ended: 2000,
             demanded_tokens: Uint128::new(100),
-            ipfs_hashes: vec!["QmHash1".to_string()],
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
         };
         execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
         let voter_info = mock_info("voter1", &[]);
@@ -311,17 +429,16 @@ mod tests {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info("creator", &[]);
-        let msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         let create_claim_msg = ExecuteMsg::CreateClaim {
-            longitudes: vec!["123.456".to_string()],
-            latitudes: vec!["78.90".to_string()],
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
             time_started: 1000,
             time_ended: 2000,
             demanded_tokens: Uint128::new(100),
-            ipfs_hashes: vec!["QmHash1".to_string()],
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
         };
         execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
         let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
@@ -334,17 +451,20 @@ mod tests {
         let mut deps = mock_dependencies();
         let mut env = mock_env();
         let info = mock_info("creator", &[]);
-        let msg = InstantiateMsg {
-            voting_period: 86400, // 1 day
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for verifier in ["voter1", "voter2"] {
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(verifier) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
         let create_claim_msg = ExecuteMsg::CreateClaim {
-            longitudes: vec!["123.456".to_string()],
-            latitudes: vec!["78.90".to_string()],
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
             time_started: 1000,
             time_ended: 2000,
             demanded_tokens: Uint128::new(100),
-            ipfs_hashes: vec!["QmHash1".to_string()],
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
         };
         execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
 
@@ -379,17 +499,18 @@ mod tests {
         let mut deps = mock_dependencies();
         let env = mock_env();
         let info = mock_info("creator", &[]);
-        let msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let msg = default_instantiate_msg();
         instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
         let create_claim_msg = ExecuteMsg::CreateClaim {
-            longitudes: vec!["123.456".to_string()],
-            latitudes: vec!["78.90".to_string()],
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
             time_started: 1000,
             time_ended: 2000,
             demanded_tokens: Uint128::new(100),
-            ipfs_hashes: vec!["QmHash1".to_string()],
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
         };
         execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
         let voter_info = mock_info("voter", &[]);
@@ -403,15 +524,25 @@ mod tests {
 
         let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
         execute(deps.as_mut(), env2.clone(), info.clone(), finalize_msg).unwrap();
-        let lend_msg = ExecuteMsg::LendTokens {
-            borrower: Addr::unchecked("borrower"),
+        let request_msg = ExecuteMsg::CreateLendToken {
+            lender: Addr::unchecked("creator"),
             amount: Uint128::new(150),
+            interest_rate: Some(Decimal::percent(10)),
+            term: 31536000,
+            proof: None,
+        };
+        execute(deps.as_mut(), env2.clone(), mock_info("borrower", &[]), request_msg).unwrap();
+        prove_lend_request(deps.as_mut(), &env2, 0);
+        let lend_msg = ExecuteMsg::LendTokens {
+            lend_request_id: 0,
+            response: "accepted".to_string(),
+            interest_rate: None,
         };
         let res = execute(deps.as_mut(), env2.clone(), info.clone(), lend_msg);
         assert!(res.is_err()); 
         let borrower_info = mock_info("borrower", &[]);
         let repay_msg = ExecuteMsg::RepayTokens {
-            lender: Addr::unchecked("creator"),
+            loan_id: 0,
             amount: Uint128::new(150),
         };
         let res = execute(deps.as_mut(), env2.clone(), borrower_info, repay_msg);
@@ -420,9 +551,7 @@ mod tests {
     #[test]
     fn update_organization_name() {
         let mut deps = mock_dependencies();
-        let instantiate_msg = InstantiateMsg {
-            voting_period: 86400,
-        };
+        let instantiate_msg = default_instantiate_msg();
         let info = mock_info("creator", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg).unwrap();
         let query_msg = QueryMsg::GetOrganization {
@@ -453,9 +582,7 @@ mod tests {
     #[test]
     fn query_all_organizations_pagination() {
         let mut deps = mock_dependencies();
-        let instantiate_msg = InstantiateMsg {
-            voting_period: 86400, 
-        };
+        let instantiate_msg = default_instantiate_msg();
         let info = mock_info("creator", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
         let info = mock_info("creator", &coins(1000, "earth"));
@@ -463,8 +590,8 @@ mod tests {
             name: "Creator Org".to_string(),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, update_name_msg).unwrap();
-        let org_addresses = vec!["org1", "org2", "org3", "org4", "org5"];
-        let org_names = vec!["First Org", "Second Org", "Third Org", "Fourth Org", "Fifth Org"];
+        let org_addresses = ["org1", "org2", "org3", "org4", "org5"];
+        let org_names = ["First Org", "Second Org", "Third Org", "Fourth Org", "Fifth Org"];
         
         for i in 0..5 {
             let info = mock_info(org_addresses[i], &coins(1000, "earth"));
@@ -510,5 +637,2473 @@ mod tests {
         assert_eq!(page3.organizations.len(), 2);
         assert_eq!(page3.organizations[0].address, Addr::unchecked("org4"));
         assert_eq!(page3.organizations[1].address, Addr::unchecked("org5"));
-    }   
+    }
+
+    #[test]
+    fn zero_vote_claim_fails_quorum() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.status, ClaimStatus::FailedQuorum);
+        let total_res: TotalCarbonCreditsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetTotalCarbonCredits {}).unwrap()).unwrap();
+        assert_eq!(total_res.total, Uint128::zero());
+        let org_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("creator") }).unwrap()).unwrap();
+        assert_eq!(org_res.carbon_credits, Uint128::zero());
+    }
+
+    #[test]
+    fn low_turnout_claim_fails_quorum() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            quorum: Decimal::percent(50),
+            dispute_quorum: Decimal::percent(50),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for org in ["org1", "org2", "org3", "org4"] {
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(org) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
+        // Organizations outside the registry cannot vote, so they do not dilute turnout
+        for org in ["org5", "org6", "org7", "org8"] {
+            let update_name_msg = ExecuteMsg::UpdateOrganizationName { name: org.to_string() };
+            execute(deps.as_mut(), env.clone(), mock_info(org, &[]), update_name_msg).unwrap();
+        }
+        let remove_verifier_msg = ExecuteMsg::RemoveVerifier { address: Addr::unchecked("org4") };
+        execute(deps.as_mut(), env.clone(), info.clone(), remove_verifier_msg).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+
+        // One voter out of three registered verifiers is below a 50% quorum
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        execute(deps.as_mut(), env.clone(), mock_info("org1", &[]), vote_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.status, ClaimStatus::FailedQuorum);
+        let voter_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("org1") }).unwrap()).unwrap();
+        assert_eq!(voter_res.reputation_score, Uint128::zero());
+        // A failed round still releases the voter's bond
+        let verifier_res: VerifierResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetVerifier { address: Addr::unchecked("org1") }).unwrap()).unwrap();
+        assert_eq!(verifier_res.pending_votes, 0);
+        let total_res: TotalCarbonCreditsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetTotalCarbonCredits {}).unwrap()).unwrap();
+        assert_eq!(total_res.total, Uint128::zero());
+    }
+
+    #[test]
+    fn pass_threshold_is_enforced() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            min_voters: 3,
+            threshold: Decimal::percent(67),
+            dispute_min_voters: 3,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for verifier in ["voter1", "voter2", "voter3"] {
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(verifier) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+        let votes = [("voter1", VoteOption::Yes), ("voter2", VoteOption::Yes), ("voter3", VoteOption::No)];
+        for (voter, vote) in votes {
+            let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote };
+            execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), vote_msg).unwrap();
+        }
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.status, ClaimStatus::Rejected);
+    }
+
+    #[test]
+    fn update_voting_config() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let update_msg = ExecuteMsg::UpdateVotingConfig {
+            voting_period: None,
+            min_voters: Some(5),
+            quorum: Some(Decimal::percent(20)),
+            threshold: None,
+            voting_power: None,
+            verifier_min_reputation: None,
+            verifier_min_bond: None,
+            commit_reveal: None,
+            reveal_period: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), update_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        // A regular vote cannot become harder to pass than a dispute round
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InvalidDisputeConfig {});
+        let dispute_msg = ExecuteMsg::UpdateDisputeConfig {
+            challenge_period: None,
+            dispute_bond: None,
+            dispute_min_voters: Some(5),
+            dispute_quorum: Some(Decimal::percent(20)),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), dispute_msg).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update_msg).unwrap();
+        let dispute_msg = ExecuteMsg::UpdateDisputeConfig {
+            challenge_period: None,
+            dispute_bond: None,
+            dispute_min_voters: Some(4),
+            dispute_quorum: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), dispute_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidDisputeConfig {});
+
+        let config_res: ConfigResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap()).unwrap();
+        assert_eq!(config_res.voting_period, 86400);
+        assert_eq!(config_res.min_voters, 5);
+        assert_eq!(config_res.quorum, Decimal::percent(20));
+        assert_eq!(config_res.threshold, Decimal::percent(50));
+
+        let invalid_msg = ExecuteMsg::UpdateVotingConfig {
+            voting_period: None,
+            min_voters: None,
+            quorum: None,
+            threshold: Some(Decimal::percent(150)),
+            voting_power: None,
+            verifier_min_reputation: None,
+            verifier_min_bond: None,
+            commit_reveal: None,
+            reveal_period: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), invalid_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidVotingConfig {});
+    }
+
+    #[test]
+    fn credit_weighted_votes_are_snapshotted() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            voting_power: VotingPowerModel::Credits,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (org, credits) in [("whale", 100u128), ("minnow1", 10), ("minnow2", 10)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::zero(),
+                carbon_credits: Uint128::new(credits * 2),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: org.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(org), &org_info).unwrap();
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(org) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+            let bond_msg = ExecuteMsg::BondCredits { amount: Uint128::new(credits) };
+            execute(deps.as_mut(), env.clone(), mock_info(org, &[]), bond_msg).unwrap();
+        }
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("fresh") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+
+        // A verifier with nothing bonded has no say under the credit-weighted model
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("fresh", &[]), vote_msg);
+        assert_eq!(res.unwrap_err(), ContractError::NoVotingPower {});
+
+        let votes = [("whale", VoteOption::No), ("minnow1", VoteOption::Yes), ("minnow2", VoteOption::Yes)];
+        for (voter, vote) in votes {
+            let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote };
+            execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), vote_msg).unwrap();
+        }
+
+        // Bonding more after voting must not change a minnow's weight
+        let bond_msg = ExecuteMsg::BondCredits { amount: Uint128::new(10) };
+        execute(deps.as_mut(), env.clone(), mock_info("minnow1", &[]), bond_msg).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.yes_votes, Uint128::new(20));
+        assert_eq!(claim_res.no_votes, Uint128::new(100));
+        assert_eq!(claim_res.status, ClaimStatus::Rejected);
+    }
+
+    #[test]
+    fn quadratic_voting_power() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            voting_power: VotingPowerModel::Quadratic,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let org_info = crate::state::OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::new(150),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "voter".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        };
+        ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked("voter"), &org_info).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        let bond_msg = ExecuteMsg::BondCredits { amount: Uint128::new(150) };
+        execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), bond_msg).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "weight" && attr.value == "12"));
+    }
+
+    #[test]
+    fn voter_eligibility_is_enforced() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            verifier_min_reputation: Uint128::new(5),
+            verifier_min_bond: Uint128::new(10),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (org, reputation) in [("creator", 10u128), ("veteran", 10), ("newcomer", 0)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::new(50),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: org.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(org), &org_info).unwrap();
+        }
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+
+        // Only the owner manages the registry
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("veteran") };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("veteran", &[]), add_verifier_msg);
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("veteran", &[]), vote_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::NotVerifier {});
+
+        for verifier in ["creator", "veteran", "newcomer"] {
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(verifier) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), vote_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::SelfVote {});
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("veteran", &[]), vote_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientBond {});
+
+        for verifier in ["veteran", "newcomer"] {
+            let bond_msg = ExecuteMsg::BondCredits { amount: Uint128::new(10) };
+            execute(deps.as_mut(), env.clone(), mock_info(verifier, &[]), bond_msg).unwrap();
+        }
+        let res = execute(deps.as_mut(), env.clone(), mock_info("newcomer", &[]), vote_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::NotEnoughReputation {});
+        execute(deps.as_mut(), env.clone(), mock_info("veteran", &[]), vote_msg).unwrap();
+
+        let verifier_res: VerifierResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetVerifier { address: Addr::unchecked("veteran") }).unwrap()).unwrap();
+        assert_eq!(verifier_res.bonded_credits, Uint128::new(10));
+        let verifiers_res: VerifiersResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetVerifiers { start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(verifiers_res.verifiers.len(), 3);
+
+        // Removing a verifier returns its bond
+        let remove_msg = ExecuteMsg::RemoveVerifier { address: Addr::unchecked("newcomer") };
+        execute(deps.as_mut(), env.clone(), info.clone(), remove_msg).unwrap();
+        let org_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("newcomer") }).unwrap()).unwrap();
+        assert_eq!(org_res.carbon_credits, Uint128::new(50));
+    }
+
+    #[test]
+    fn incorrect_votes_are_slashed() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            vote_reward: Uint128::new(2),
+            vote_slash: Uint128::new(3),
+            bond_slash: Decimal::percent(10),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (org, reputation) in [("voter1", 0u128), ("voter2", 0), ("voter3", 5)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::new(100),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: org.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(org), &org_info).unwrap();
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(org) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+            let bond_msg = ExecuteMsg::BondCredits { amount: Uint128::new(100) };
+            execute(deps.as_mut(), env.clone(), mock_info(org, &[]), bond_msg).unwrap();
+        }
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+        let votes = [("voter1", VoteOption::Yes), ("voter2", VoteOption::No), ("voter3", VoteOption::No)];
+        for (voter, vote) in votes {
+            let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote };
+            execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), vote_msg).unwrap();
+        }
+        // The bond behind a pending vote cannot be pulled out before the slash lands
+        let unbond_msg = ExecuteMsg::UnbondCredits { amount: Uint128::new(100) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voter1", &[]), unbond_msg);
+        assert_eq!(res.unwrap_err(), ContractError::BondLocked {});
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg.clone()).unwrap();
+        assert_eq!(res.events.iter().filter(|event| event.ty == "vote_outcome").count(), 3);
+
+        // Claim is rejected 2-1; the lone Yes voter loses reputation floored at zero
+        let voter1_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("voter1") }).unwrap()).unwrap();
+        assert_eq!(voter1_res.reputation_score, Uint128::zero());
+        let voter1_bond: VerifierResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetVerifier { address: Addr::unchecked("voter1") }).unwrap()).unwrap();
+        assert_eq!(voter1_bond.bonded_credits, Uint128::new(90));
+        let voter3_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("voter3") }).unwrap()).unwrap();
+        assert_eq!(voter3_res.reputation_score, Uint128::new(7));
+
+        let record_res: VotingRecordResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetVotingRecord { address: Addr::unchecked("voter1"), start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(record_res.records.len(), 1);
+        assert!(!record_res.records[0].correct);
+        assert_eq!(record_res.records[0].reputation_lost, Uint128::zero());
+        assert_eq!(record_res.records[0].bond_slashed, Uint128::new(10));
+
+        // Finalizing twice must not apply the schedule again
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg);
+        assert_eq!(res.unwrap_err(), ContractError::ClaimNotActive {});
+
+        // Once the vote is settled the rest of the bond is free again
+        assert_eq!(voter1_bond.pending_votes, 0);
+        let unbond_msg = ExecuteMsg::UnbondCredits { amount: Uint128::new(90) };
+        execute(deps.as_mut(), env.clone(), mock_info("voter1", &[]), unbond_msg).unwrap();
+    }
+
+    #[test]
+    fn commit_reveal_voting() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            commit_reveal: true,
+            reveal_period: 3600,
+            unrevealed_slash: Uint128::new(2),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (org, reputation) in [("voter1", 0u128), ("voter2", 0), ("voter3", 5)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::zero(),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: org.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(org), &org_info).unwrap();
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(org) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+
+        // Plain votes are refused while commit-reveal is on
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voter1", &[]), vote_msg);
+        assert_eq!(res.unwrap_err(), ContractError::CommitRevealRequired {});
+
+        let votes = [("voter1", VoteOption::Yes), ("voter2", VoteOption::No), ("voter3", VoteOption::No)];
+        for (voter, vote) in votes.iter() {
+            let commitment = vote_commitment_hash(&Addr::unchecked(*voter), 0, vote, "salt");
+            let commit_msg = ExecuteMsg::CommitVote { claim_id: 0, commitment };
+            execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), commit_msg).unwrap();
+        }
+
+        let reveal_msg = ExecuteMsg::RevealVote { claim_id: 0, vote: VoteOption::Yes, salt: "salt".to_string() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voter1", &[]), reveal_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::RevealNotStarted {});
+
+        env.block.time = env.block.time.plus_seconds(86401);
+        execute(deps.as_mut(), env.clone(), mock_info("voter1", &[]), reveal_msg).unwrap();
+
+        // A reveal that does not match the commitment is rejected
+        let bad_reveal_msg = ExecuteMsg::RevealVote { claim_id: 0, vote: VoteOption::Yes, salt: "salt".to_string() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voter2", &[]), bad_reveal_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidCommitment {});
+
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::VotingNotEnded {});
+
+        // voter2 and voter3 never reveal, so the single Yes decides the claim
+        env.block.time = env.block.time.plus_seconds(3600);
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.status, ClaimStatus::Approved);
+        assert_eq!(claim_res.yes_votes, Uint128::new(1));
+        assert_eq!(claim_res.no_votes, Uint128::zero());
+
+        let voter3_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("voter3") }).unwrap()).unwrap();
+        assert_eq!(voter3_res.reputation_score, Uint128::new(3));
+        let voter1_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("voter1") }).unwrap()).unwrap();
+        assert_eq!(voter1_res.reputation_score, Uint128::new(1));
+    }
+
+    #[test]
+    fn dispute_overturns_approval() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            dispute_bond: Uint128::new(20),
+            dispute_min_voters: 3,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for org in ["voter1", "voter2", "voter3", "challenger"] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::zero(),
+                carbon_credits: Uint128::new(50),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: org.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(org), &org_info).unwrap();
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(org) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        execute(deps.as_mut(), env.clone(), mock_info("voter1", &[]), vote_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg.clone()).unwrap();
+        let total_res: TotalCarbonCreditsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetTotalCarbonCredits {}).unwrap()).unwrap();
+        assert_eq!(total_res.total, Uint128::new(100));
+
+        let creator_info = mock_info("creator", &[]);
+        let dispute_msg = ExecuteMsg::DisputeClaim { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), mock_info("challenger", &[]), dispute_msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), creator_info, dispute_msg);
+        assert_eq!(res.unwrap_err(), ContractError::ClaimNotDisputable {});
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.status, ClaimStatus::Disputed);
+        let challenger_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("challenger") }).unwrap()).unwrap();
+        assert_eq!(challenger_res.carbon_credits, Uint128::new(30));
+
+        // The disputer cannot vote in its own dispute, earlier voters may vote again
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::No };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("challenger", &[]), vote_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::SelfVote {});
+        for voter in ["voter1", "voter2", "voter3"] {
+            execute(deps.as_mut(), env.clone(), mock_info(voter, &[]), vote_msg.clone()).unwrap();
+        }
+        env.block.time = env.block.time.plus_seconds(86401);
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.status, ClaimStatus::Rejected);
+        let dispute_res: DisputeResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetDispute { claim_id: 0 }).unwrap()).unwrap();
+        assert_eq!(dispute_res.status, DisputeStatus::Overturned);
+        // The first round's reward is settled, voter1 is only rewarded for the final outcome
+        let voter1_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("voter1") }).unwrap()).unwrap();
+        assert_eq!(voter1_res.reputation_score, Uint128::new(1));
+        let record_res: VotingRecordResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetVotingRecord { address: Addr::unchecked("voter1"), start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(record_res.records.len(), 1);
+        assert_eq!(record_res.records[0].vote, VoteOption::No);
+        assert!(record_res.records[0].correct);
+        let creator_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("creator") }).unwrap()).unwrap();
+        assert_eq!(creator_res.carbon_credits, Uint128::zero());
+        let total_res: TotalCarbonCreditsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetTotalCarbonCredits {}).unwrap()).unwrap();
+        assert_eq!(total_res.total, Uint128::zero());
+        let challenger_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("challenger") }).unwrap()).unwrap();
+        assert_eq!(challenger_res.carbon_credits, Uint128::new(50));
+
+        // Only one dispute per claim
+        let dispute_msg = ExecuteMsg::DisputeClaim { claim_id: 0 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voter1", &[]), dispute_msg);
+        assert_eq!(res.unwrap_err(), ContractError::AlreadyDisputed {});
+    }
+
+    #[test]
+    fn dispute_after_challenge_period_fails() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            challenge_period: 3600,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(3601);
+        let dispute_msg = ExecuteMsg::DisputeClaim { claim_id: 0 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), dispute_msg);
+        assert_eq!(res.unwrap_err(), ContractError::ChallengePeriodEnded {});
+    }
+
+    #[test]
+    fn claim_geodata_is_normalized() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Clockwise ring with a repeated vertex
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["-1".to_string(), "-1.0".to_string(), "-1".to_string(), "1".to_string(), "+1.000000".to_string(), "-1".to_string()],
+            latitudes: vec!["-1".to_string(), "1".to_string(), "1".to_string(), "1".to_string(), "-1".to_string(), "-1".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.polygon.vertices, vec![
+            Coordinate { longitude: -1_000_000, latitude: -1_000_000 },
+            Coordinate { longitude: 1_000_000, latitude: -1_000_000 },
+            Coordinate { longitude: 1_000_000, latitude: 1_000_000 },
+            Coordinate { longitude: -1_000_000, latitude: 1_000_000 },
+            Coordinate { longitude: -1_000_000, latitude: -1_000_000 },
+        ]);
+        // 2 x 2 degrees around the equator, roughly 49_452 km2
+        assert_eq!(claim_res.polygon.area, Uint128::new(49_449_717_577));
+    }
+
+    #[test]
+    fn malformed_geodata_is_rejected() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let cases = vec![
+            (vec!["0", "1", "1", "0"], vec!["0", "0", "1", "1", "0"], ContractError::CoordinateCountMismatch {}),
+            (vec!["0", "1", "east", "0"], vec!["0", "0", "1", "0"], ContractError::InvalidCoordinate { value: "east".to_string() }),
+            (vec!["0", "1.0000001", "1", "0"], vec!["0", "0", "1", "0"], ContractError::InvalidCoordinate { value: "1.0000001".to_string() }),
+            (vec!["0", "180.5", "1", "0"], vec!["0", "0", "1", "0"], ContractError::LongitudeOutOfRange {}),
+            (vec!["0", "1", "1", "0"], vec!["0", "0", "-90.1", "0"], ContractError::LatitudeOutOfRange {}),
+            (vec!["0", "1", "1", "0"], vec!["0", "0", "1", "1"], ContractError::PolygonNotClosed {}),
+            (vec!["0", "1", "0"], vec!["0", "1", "0"], ContractError::TooFewVertices {}),
+            (vec!["0", "1", "2", "0"], vec!["0", "1", "2", "0"], ContractError::SelfIntersectingPolygon {}),
+            (vec!["0", "1", "0", "0"], vec!["90", "89.999999", "89.999999", "90"], ContractError::ZeroAreaPolygon {}),
+            (vec!["0", "1", "0", "1", "0"], vec!["0", "1", "1", "0", "0"], ContractError::SelfIntersectingPolygon {}),
+        ];
+        for (longitudes, latitudes, error) in cases {
+            let create_claim_msg = ExecuteMsg::CreateClaim {
+                longitudes: longitudes.iter().map(|l| l.to_string()).collect(),
+                latitudes: latitudes.iter().map(|l| l.to_string()).collect(),
+                time_started: 1000,
+                time_ended: 2000,
+                demanded_tokens: Uint128::new(100),
+                ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+                methodology: "forestry".to_string(),
+            };
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg);
+            assert_eq!(res.unwrap_err(), error);
+        }
+    }
+
+    #[test]
+    fn overlapping_claims_are_rejected() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let square = |west: &str, east: &str, south: &str, north: &str| {
+            (
+                vec![west, east, east, west, west].into_iter().map(String::from).collect::<Vec<_>>(),
+                vec![south, south, north, north, south].into_iter().map(String::from).collect::<Vec<_>>(),
+            )
+        };
+        let create_claim = |longitudes: Vec<String>, latitudes: Vec<String>, time_started: u64, time_ended: u64| ExecuteMsg::CreateClaim {
+            longitudes,
+            latitudes,
+            time_started,
+            time_ended,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+
+        let (longitudes, latitudes) = square("10", "10.5", "20", "20.5");
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim(longitudes, latitudes, 1000, 2000)).unwrap();
+
+        // Another organization claiming part of the same land over the same period
+        let (longitudes, latitudes) = square("10.4", "10.9", "20.4", "20.9");
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), create_claim(longitudes, latitudes, 1500, 2500));
+        assert_eq!(res.unwrap_err(), ContractError::OverlappingClaim { claim_id: 0 });
+        // The same land twice, or a parcel nested inside it
+        let (longitudes, latitudes) = square("10", "10.5", "20", "20.5");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), create_claim(longitudes, latitudes, 1000, 2000));
+        assert_eq!(res.unwrap_err(), ContractError::OverlappingClaim { claim_id: 0 });
+        let (longitudes, latitudes) = square("10.1", "10.2", "20.1", "20.2");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), create_claim(longitudes, latitudes, 1000, 2000));
+        assert_eq!(res.unwrap_err(), ContractError::OverlappingClaim { claim_id: 0 });
+
+        // Neighbouring parcels and later periods do not conflict
+        let (longitudes, latitudes) = square("10.5", "11", "20", "20.5");
+        execute(deps.as_mut(), env.clone(), mock_info("other", &[]), create_claim(longitudes, latitudes, 1000, 2000)).unwrap();
+        let (longitudes, latitudes) = square("10", "10.5", "20", "20.5");
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim(longitudes, latitudes, 2000, 3000)).unwrap();
+
+        let (longitudes, latitudes) = square("10.4", "10.6", "20.4", "20.6");
+        let query_msg = QueryMsg::GetConflictingClaims { longitudes, latitudes, time_started: 1000, time_ended: 1500 };
+        let conflicts_res: ConflictingClaimsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(conflicts_res.claim_ids, vec![0, 1]);
+
+        // Claims that fail are dropped from the index
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::No };
+        execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+        let (longitudes, latitudes) = square("10.1", "10.2", "20.1", "20.2");
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim(longitudes, latitudes, 1000, 2000)).unwrap();
+
+        // The rejection cannot be disputed back onto land claimed since
+        let dispute_msg = ExecuteMsg::DisputeClaim { claim_id: 0 };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), dispute_msg);
+        assert_eq!(res.unwrap_err(), ContractError::OverlappingClaim { claim_id: 3 });
+    }
+
+    #[test]
+    fn claim_period_and_evidence_are_validated() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let now = env.block.time.seconds();
+        let cases = vec![
+            (2000, 1000, vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"], ContractError::InvalidClaimPeriod {}),
+            (1000, 1000, vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"], ContractError::InvalidClaimPeriod {}),
+            (now - 10, now + 10, vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"], ContractError::ClaimPeriodNotEnded {}),
+            (1000, 1000 + 31536001, vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"], ContractError::ClaimPeriodTooLong { max: 31536000 }),
+            (1000, 2000, vec![], ContractError::MissingEvidence {}),
+            (1000, 2000, vec!["QmHash1"], ContractError::InvalidCid { cid: "QmHash1".to_string() }),
+            // '0' is not part of the base58 alphabet
+            (1000, 2000, vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0"], ContractError::InvalidCid { cid: "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0".to_string() }),
+            // Digest shorter than the multihash header claims
+            (1000, 2000, vec!["bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbz"], ContractError::InvalidCid { cid: "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbz".to_string() }),
+            (1000, 2000, vec!["https://example.com/evidence.pdf"], ContractError::InvalidCid { cid: "https://example.com/evidence.pdf".to_string() }),
+        ];
+        for (time_started, time_ended, ipfs_hashes, error) in cases {
+            let create_claim_msg = ExecuteMsg::CreateClaim {
+                longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+                latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+                time_started,
+                time_ended,
+                demanded_tokens: Uint128::new(100),
+                ipfs_hashes: ipfs_hashes.into_iter().map(String::from).collect(),
+                methodology: "forestry".to_string(),
+            };
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg);
+            assert_eq!(res.unwrap_err(), error);
+        }
+
+        // CIDv1 in base32 and base16 multibase
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec![
+                "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+                "f01701220c3c4733ec8affd06cf9e9ff50ffc6bcd2ec85a6170004bb709669c31de94391a".to_string(),
+            ],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg).unwrap();
+
+        // Only the owner can change the maximum period
+        let update_msg = ExecuteMsg::UpdateClaimConfig { max_claim_period: 500 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), update_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), info.clone(), update_msg).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 2000,
+            time_ended: 3000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), create_claim_msg);
+        assert_eq!(res.unwrap_err(), ContractError::ClaimPeriodTooLong { max: 500 });
+    }
+
+    #[test]
+    fn demanded_tokens_are_capped_by_methodology() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let mangrove = Methodology {
+            id: "mangrove".to_string(),
+            land_type: "wetland".to_string(),
+            credits_per_hectare_year: Decimal::from_ratio(10u128, 1u128),
+        };
+        let set_msg = ExecuteMsg::SetMethodology { methodology: mangrove.clone() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), set_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), info.clone(), set_msg).unwrap();
+        let methodologies_res: MethodologiesResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetMethodologies { start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(methodologies_res.methodologies.len(), 2);
+        assert_eq!(methodologies_res.methodologies[1].credits_per_hectare_year, mangrove.credits_per_hectare_year);
+
+        // About 123.6 hectares at the equator for one year
+        let longitudes = vec!["0".to_string(), "0.01".to_string(), "0.01".to_string(), "0".to_string(), "0".to_string()];
+        let latitudes = vec!["0".to_string(), "0".to_string(), "0.01".to_string(), "0.01".to_string(), "0".to_string()];
+        let preview_msg = QueryMsg::PreviewClaimCap {
+            methodology: "mangrove".to_string(),
+            longitudes: longitudes.clone(),
+            latitudes: latitudes.clone(),
+            time_started: 1000,
+            time_ended: 1000 + 31536000,
+        };
+        let cap_res: ClaimCapResponse = from_binary(&query(deps.as_ref(), env.clone(), preview_msg).unwrap()).unwrap();
+        assert_eq!(cap_res.area, Uint128::new(1_236_372));
+        assert_eq!(cap_res.period, 31536000);
+        assert_eq!(cap_res.max_tokens, Uint128::new(1236));
+
+        let create_claim = |demanded_tokens: u128, methodology: &str| ExecuteMsg::CreateClaim {
+            longitudes: longitudes.clone(),
+            latitudes: latitudes.clone(),
+            time_started: 1000,
+            time_ended: 1000 + 31536000,
+            demanded_tokens: Uint128::new(demanded_tokens),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: methodology.to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), create_claim(1236, "peatland"));
+        assert_eq!(res.unwrap_err(), ContractError::MethodologyNotFound {});
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), create_claim(1237, "mangrove"));
+        assert_eq!(res.unwrap_err(), ContractError::DemandExceedsCap { max: Uint128::new(1236) });
+        execute(deps.as_mut(), env.clone(), info.clone(), create_claim(1236, "mangrove")).unwrap();
+
+        let claim_res: ClaimResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetClaim { id: 0 }).unwrap()).unwrap();
+        assert_eq!(claim_res.methodology, "mangrove");
+
+        let remove_msg = ExecuteMsg::RemoveMethodology { id: "mangrove".to_string() };
+        execute(deps.as_mut(), env.clone(), info.clone(), remove_msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), remove_msg);
+        assert_eq!(res.unwrap_err(), ContractError::MethodologyNotFound {});
+    }
+
+    #[test]
+    fn approved_claims_mint_credit_tokens() {
+        let mut app = App::default();
+        let token_code_id = app.store_code(Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        let contract_code_id = app.store_code(Box::new(
+            ContractWrapper::new(execute, instantiate, query).with_reply(reply),
+        ));
+        let msg = InstantiateMsg {
+            credit_token: Some(CreditTokenInfo {
+                code_id: token_code_id,
+                name: "Carbon Credit".to_string(),
+                symbol: "CARBON".to_string(),
+                decimals: 0,
+            }),
+            ..default_instantiate_msg()
+        };
+        let contract = app
+            .instantiate_contract(contract_code_id, Addr::unchecked("creator"), &msg, &[], "carbon", None)
+            .unwrap();
+        let config_res: ConfigResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetConfig {}).unwrap();
+        let token = config_res.credit_token.unwrap();
+        let balance = |app: &App, address: &str| -> Uint128 {
+            let res: cw20::BalanceResponse = app.wrap()
+                .query_wasm_smart(&token, &cw20::Cw20QueryMsg::Balance { address: address.to_string() })
+                .unwrap();
+            res.balance
+        };
+
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &add_verifier_msg, &[]).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        app.execute_contract(Addr::unchecked("lender"), contract.clone(), &create_claim_msg, &[]).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        app.execute_contract(Addr::unchecked("voter"), contract.clone(), &vote_msg, &[]).unwrap();
+        app.update_block(|block| block.time = block.time.plus_seconds(86401));
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_msg, &[]).unwrap();
+        let credits = |app: &App, address: &str| -> Uint128 {
+            let res: OrganizationResponse = app.wrap()
+                .query_wasm_smart(&contract, &QueryMsg::GetOrganization { address: Addr::unchecked(address) })
+                .unwrap();
+            res.carbon_credits
+        };
+        // Issued credits are minted into the contract, which holds them for the claimant
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(100));
+        assert_eq!(balance(&app, "lender"), Uint128::zero());
+        assert_eq!(credits(&app, "lender"), Uint128::new(100));
+
+        // Lending moves the credits between the balances the contract holds
+        let lend_request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(40), interest_rate: Some(Decimal::percent(10)), term: 31536000, proof: None };
+        app.execute_contract(Addr::unchecked("borrower"), contract.clone(), &lend_request_msg, &[]).unwrap();
+        let (proving_key, verifying_key) = proof_keys();
+        let update_msg = ExecuteMsg::UpdateVerifyingKey { verifying_key: Binary::from(verifying_key.clone()) };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &update_msg, &[]).unwrap();
+        let inputs = EligibilityInputs { salt: SALT, lender_credits: 100, ..EligibilityInputs::default() };
+        let commitment = Binary::from(inputs.borrower.commitment(&SALT));
+        let commit_msg = ExecuteMsg::CommitEligibility { commitment: commitment.clone() };
+        app.execute_contract(Addr::unchecked("borrower"), contract.clone(), &commit_msg, &[]).unwrap();
+        let attest_msg = ExecuteMsg::AttestEligibility { borrower: Addr::unchecked("borrower"), commitment };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &attest_msg, &[]).unwrap();
+        let (_, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(0)).unwrap();
+        let public_inputs = inputs.public_inputs().into_iter().map(Binary::from).collect();
+        let verify_msg = ExecuteMsg::VerifyProof { lend_request_id: 0, proof: Binary::from(proof), public_inputs };
+        app.execute_contract(Addr::unchecked("borrower"), contract.clone(), &verify_msg, &[]).unwrap();
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 0, response: "accepted".to_string(), interest_rate: None };
+        app.execute_contract(Addr::unchecked("lender"), contract.clone(), &lend_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "lender"), Uint128::new(60));
+        assert_eq!(credits(&app, "borrower"), Uint128::new(40));
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(100));
+
+        // Withdrawn credits leave the contract's books along with the tokens
+        let withdraw_msg = ExecuteMsg::WithdrawCredits { amount: Uint128::new(41) };
+        let err = app.execute_contract(Addr::unchecked("borrower"), contract.clone(), &withdraw_msg, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NotEnoughCredits {});
+        let withdraw_msg = ExecuteMsg::WithdrawCredits { amount: Uint128::new(25) };
+        app.execute_contract(Addr::unchecked("borrower"), contract.clone(), &withdraw_msg, &[]).unwrap();
+        assert_eq!(balance(&app, "borrower"), Uint128::new(25));
+        assert_eq!(credits(&app, "borrower"), Uint128::new(15));
+
+        // Tokens moved between wallets only count here once sent back in
+        let transfer_msg = cw20::Cw20ExecuteMsg::Transfer { recipient: "outsider".to_string(), amount: Uint128::new(10) };
+        app.execute_contract(Addr::unchecked("borrower"), token.clone(), &transfer_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "borrower"), Uint128::new(15));
+        let send_msg = cw20::Cw20ExecuteMsg::Send { contract: contract.to_string(), amount: Uint128::new(10), msg: to_binary(&ReceiveMsg::Deposit {}).unwrap() };
+        app.execute_contract(Addr::unchecked("outsider"), token.clone(), &send_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "outsider"), Uint128::new(10));
+        let receive_msg = ExecuteMsg::Receive(cw20::Cw20ReceiveMsg { sender: "borrower".to_string(), amount: Uint128::new(100), msg: to_binary(&ReceiveMsg::Deposit {}).unwrap() });
+        let err = app.execute_contract(Addr::unchecked("borrower"), contract.clone(), &receive_msg, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(15) };
+        app.execute_contract(Addr::unchecked("borrower"), contract.clone(), &repay_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "lender"), Uint128::new(75));
+        assert_eq!(credits(&app, "borrower"), Uint128::zero());
+        // Every recorded balance is backed by a token the contract holds
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(85));
+    }
+
+    #[test]
+    fn retire_credits_issues_certificates() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+
+        let org_info = mock_info("org", &[]);
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), org_info.clone(), create_claim_msg).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+        let emission_msg = ExecuteMsg::AddOrganizationEmission { emissions: "50".to_string() };
+        execute(deps.as_mut(), env.clone(), org_info.clone(), emission_msg).unwrap();
+
+        let retire_msg = ExecuteMsg::RetireCredits { amount: Uint128::zero(), beneficiary: "Org Inc.".to_string(), reason: "2023 offset".to_string() };
+        let res = execute(deps.as_mut(), env.clone(), org_info.clone(), retire_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidRetirementAmount {});
+        let retire_msg = ExecuteMsg::RetireCredits { amount: Uint128::new(101), beneficiary: "Org Inc.".to_string(), reason: "2023 offset".to_string() };
+        let res = execute(deps.as_mut(), env.clone(), org_info.clone(), retire_msg);
+        assert_eq!(res.unwrap_err(), ContractError::NotEnoughCredits {});
+
+        let retire_msg = ExecuteMsg::RetireCredits { amount: Uint128::new(30), beneficiary: "Org Inc.".to_string(), reason: "2023 offset".to_string() };
+        execute(deps.as_mut(), env.clone(), org_info.clone(), retire_msg).unwrap();
+        let retire_msg = ExecuteMsg::RetireCredits { amount: Uint128::new(40), beneficiary: "Customer Ltd.".to_string(), reason: "Flight offset".to_string() };
+        execute(deps.as_mut(), env.clone(), org_info.clone(), retire_msg).unwrap();
+
+        let org_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrganization { address: Addr::unchecked("org") }).unwrap()).unwrap();
+        assert_eq!(org_res.carbon_credits, Uint128::new(30));
+        assert_eq!(org_res.emissions, Uint128::zero());
+        let total_res: TotalCarbonCreditsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetTotalCarbonCredits {}).unwrap()).unwrap();
+        assert_eq!(total_res.total, Uint128::new(30));
+
+        let certificate: RetirementCertificate = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetRetirement { id: 0 }).unwrap()).unwrap();
+        assert_eq!(certificate.organization, Addr::unchecked("org"));
+        assert_eq!(certificate.beneficiary, "Org Inc.");
+        assert_eq!(certificate.amount, Uint128::new(30));
+        assert_eq!(certificate.reason, "2023 offset");
+        assert_eq!(certificate.block_height, env.block.height);
+        assert_eq!(certificate.batches, vec![CreditBatch { claim_id: 0, vintage: 1970, amount: Uint128::new(30) }]);
+
+        let query_msg = QueryMsg::GetRetirementsByOrganization { organization: Addr::unchecked("org"), start_after: None, limit: None };
+        let retirements_res: RetirementsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(retirements_res.retirements.len(), 2);
+        let query_msg = QueryMsg::GetRetirementsByOrganization { organization: Addr::unchecked("org"), start_after: Some(0), limit: None };
+        let retirements_res: RetirementsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(retirements_res.retirements.len(), 1);
+        assert_eq!(retirements_res.retirements[0].id, 1);
+        assert_eq!(retirements_res.retirements[0].beneficiary, "Customer Ltd.");
+    }
+
+    #[test]
+    fn credit_batches_track_provenance() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        let borrower = crate::state::OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::new(10),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "borrower".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        };
+        ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked("borrower"), &borrower).unwrap();
+
+        // Claims ending in 1970 and 2018
+        let lender_info = mock_info("lender", &[]);
+        for (claim_id, (time_started, time_ended)) in vec![(1000, 2000), (1_520_000_000, 1_530_000_000)].into_iter().enumerate() {
+            let create_claim_msg = ExecuteMsg::CreateClaim {
+                longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+                latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+                time_started,
+                time_ended,
+                demanded_tokens: Uint128::new(100),
+                ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+                methodology: "forestry".to_string(),
+            };
+            execute(deps.as_mut(), env.clone(), lender_info.clone(), create_claim_msg).unwrap();
+            let vote_msg = ExecuteMsg::CastVote { claim_id: claim_id as u64, vote: VoteOption::Yes };
+            execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+            env.block.time = env.block.time.plus_seconds(86401);
+            let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: claim_id as u64 };
+            execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+        }
+
+        // Lending hands over the oldest batches first
+        let lend_request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(120), interest_rate: Some(Decimal::percent(10)), term: 31536000, proof: None };
+        execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), lend_request_msg).unwrap();
+        prove_lend_request(deps.as_mut(), &env, 0);
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 0, response: "accepted".to_string(), interest_rate: None };
+        execute(deps.as_mut(), env.clone(), lender_info.clone(), lend_msg).unwrap();
+        let query_msg = QueryMsg::GetCreditBatches { organization: Addr::unchecked("borrower"), start_after: None, limit: None };
+        let batches_res: CreditBatchesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(batches_res.batches, vec![
+            CreditBatch { claim_id: 0, vintage: 1970, amount: Uint128::new(100) },
+            CreditBatch { claim_id: 1, vintage: 2018, amount: Uint128::new(20) },
+        ]);
+        assert_eq!(batches_res.unattributed, Uint128::new(10));
+
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(30) };
+        execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), repay_msg).unwrap();
+        let query_msg = QueryMsg::GetCreditBatches { organization: Addr::unchecked("lender"), start_after: None, limit: None };
+        let batches_res: CreditBatchesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(batches_res.batches, vec![
+            CreditBatch { claim_id: 0, vintage: 1970, amount: Uint128::new(30) },
+            CreditBatch { claim_id: 1, vintage: 2018, amount: Uint128::new(80) },
+        ]);
+        assert_eq!(batches_res.unattributed, Uint128::zero());
+
+        // Retirement certificates record the consumed batches
+        let retire_msg = ExecuteMsg::RetireCredits { amount: Uint128::new(50), beneficiary: "Lender".to_string(), reason: "Offset".to_string() };
+        execute(deps.as_mut(), env.clone(), lender_info.clone(), retire_msg).unwrap();
+        let certificate: RetirementCertificate = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetRetirement { id: 0 }).unwrap()).unwrap();
+        assert_eq!(certificate.batches, vec![
+            CreditBatch { claim_id: 0, vintage: 1970, amount: Uint128::new(30) },
+            CreditBatch { claim_id: 1, vintage: 2018, amount: Uint128::new(20) },
+        ]);
+        let query_msg = QueryMsg::GetCreditBatches { organization: Addr::unchecked("lender"), start_after: None, limit: None };
+        let batches_res: CreditBatchesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(batches_res.batches, vec![CreditBatch { claim_id: 1, vintage: 2018, amount: Uint128::new(60) }]);
+    }
+
+    #[test]
+    fn transfer_and_allowances() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let org_info = crate::state::OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::new(100),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "seller".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        };
+        ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked("seller"), &org_info).unwrap();
+        let seller_info = mock_info("seller", &[]);
+        let credits = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, address: &str| -> Uint128 {
+            let res: OrganizationResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOrganization { address: Addr::unchecked(address) }).unwrap()).unwrap();
+            res.carbon_credits
+        };
+
+        let transfer_msg = ExecuteMsg::TransferCredits { recipient: Addr::unchecked("buyer"), amount: Uint128::zero() };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), transfer_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidAmount {});
+        let transfer_msg = ExecuteMsg::TransferCredits { recipient: Addr::unchecked("seller"), amount: Uint128::new(10) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), transfer_msg);
+        assert_eq!(res.unwrap_err(), ContractError::CannotSetOwnAccount {});
+        let transfer_msg = ExecuteMsg::TransferCredits { recipient: Addr::unchecked("buyer"), amount: Uint128::new(101) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), transfer_msg);
+        assert_eq!(res.unwrap_err(), ContractError::NotEnoughCredits {});
+        let transfer_msg = ExecuteMsg::TransferCredits { recipient: Addr::unchecked("buyer"), amount: Uint128::new(30) };
+        execute(deps.as_mut(), env.clone(), seller_info.clone(), transfer_msg).unwrap();
+        assert_eq!(credits(&deps, "seller"), Uint128::new(70));
+        assert_eq!(credits(&deps, "buyer"), Uint128::new(30));
+
+        // A broker settles on the seller's behalf within its allowance
+        let expires = Expiration::AtHeight(env.block.height + 10);
+        let approve_msg = ExecuteMsg::ApproveCredits { spender: Addr::unchecked("broker"), amount: Uint128::new(50), expires: Some(expires) };
+        execute(deps.as_mut(), env.clone(), seller_info.clone(), approve_msg).unwrap();
+        let transfer_from_msg = ExecuteMsg::TransferCreditsFrom { owner: Addr::unchecked("seller"), recipient: Addr::unchecked("buyer"), amount: Uint128::new(20) };
+        execute(deps.as_mut(), env.clone(), mock_info("broker", &[]), transfer_from_msg).unwrap();
+        assert_eq!(credits(&deps, "seller"), Uint128::new(50));
+        assert_eq!(credits(&deps, "buyer"), Uint128::new(50));
+        let query_msg = QueryMsg::GetAllowance { owner: Addr::unchecked("seller"), spender: Addr::unchecked("broker") };
+        let allowance_res: AllowanceResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(allowance_res.amount, Uint128::new(30));
+        assert_eq!(allowance_res.expires, expires);
+
+        let transfer_from_msg = ExecuteMsg::TransferCreditsFrom { owner: Addr::unchecked("seller"), recipient: Addr::unchecked("buyer"), amount: Uint128::new(31) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("broker", &[]), transfer_from_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientAllowance {});
+        env.block.height += 10;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("broker", &[]), transfer_from_msg);
+        assert_eq!(res.unwrap_err(), ContractError::AllowanceExpired {});
+        let approve_msg = ExecuteMsg::ApproveCredits { spender: Addr::unchecked("broker"), amount: Uint128::new(5), expires: Some(Expiration::AtHeight(env.block.height)) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), approve_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidExpiration {});
+
+        for spender in ["auditor", "broker"] {
+            let approve_msg = ExecuteMsg::ApproveCredits { spender: Addr::unchecked(spender), amount: Uint128::new(5), expires: None };
+            execute(deps.as_mut(), env.clone(), seller_info.clone(), approve_msg).unwrap();
+        }
+        let query_msg = QueryMsg::GetAllowances { owner: Addr::unchecked("seller"), start_after: Some(Addr::unchecked("auditor")), limit: None };
+        let allowances_res: AllowancesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(allowances_res.allowances.len(), 1);
+        assert_eq!(allowances_res.allowances[0].spender, Addr::unchecked("broker"));
+        assert_eq!(allowances_res.allowances[0].expires, Expiration::Never {});
+
+        let revoke_msg = ExecuteMsg::RevokeCredits { spender: Addr::unchecked("broker") };
+        execute(deps.as_mut(), env.clone(), seller_info.clone(), revoke_msg).unwrap();
+        let transfer_from_msg = ExecuteMsg::TransferCreditsFrom { owner: Addr::unchecked("seller"), recipient: Addr::unchecked("buyer"), amount: Uint128::new(1) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("broker", &[]), transfer_from_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientAllowance {});
+    }
+
+
+    #[test]
+    fn order_book_matches_and_cancels() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let org_info = crate::state::OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::new(100),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "seller".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        };
+        ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked("seller"), &org_info).unwrap();
+        let batch = CreditBatch { claim_id: 7, vintage: 2018, amount: Uint128::new(60) };
+        CREDIT_BATCHES.save(deps.as_mut().storage, (&Addr::unchecked("seller"), 7), &batch).unwrap();
+        let seller_info = mock_info("seller", &[]);
+        let credits = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, address: &str| -> Uint128 {
+            let res: OrganizationResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOrganization { address: Addr::unchecked(address) }).unwrap()).unwrap();
+            res.carbon_credits
+        };
+        let send = |to: &str, amount: u128| -> SubMsg {
+            SubMsg::new(BankMsg::Send { to_address: to.to_string(), amount: coins(amount, "inj") })
+        };
+
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "inj".to_string(), price: Uint128::zero(), amount: Uint128::new(40) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidOrder {});
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "inj".to_string(), price: Uint128::new(10), amount: Uint128::new(101) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg);
+        assert_eq!(res.unwrap_err(), ContractError::NotEnoughCredits {});
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "inj".to_string(), price: Uint128::new(12), amount: Uint128::new(30) };
+        execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg).unwrap();
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "inj".to_string(), price: Uint128::new(10), amount: Uint128::new(40) };
+        execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg).unwrap();
+        assert_eq!(credits(&deps, "seller"), Uint128::new(30));
+
+        // Asks are listed cheapest first and paginate by order id
+        let query_msg = QueryMsg::GetOrderBook { denom: "inj".to_string(), side: OrderSide::Ask, start_after: None, limit: Some(1) };
+        let book: OrderBookResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(book.orders.len(), 1);
+        assert_eq!(book.orders[0].id, 1);
+        let query_msg = QueryMsg::GetOrderBook { denom: "inj".to_string(), side: OrderSide::Ask, start_after: Some(1), limit: None };
+        let book: OrderBookResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(book.orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![0]);
+
+        let bid_msg = ExecuteMsg::PlaceBid { price: Uint128::new(11), amount: Uint128::new(50) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(500, "inj")), bid_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InvalidFunds { expected: Uint128::new(550) });
+
+        // The bid takes the 10 ask at its price, gets the difference back and rests with the rest
+        let res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(550, "inj")), bid_msg).unwrap();
+        assert_eq!(res.messages, vec![send("seller", 400), send("buyer", 40)]);
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(credits(&deps, "buyer"), Uint128::new(40));
+        // The first ask escrowed 30 of the batch, so the 10 ask only held the other 30
+        let query_msg = QueryMsg::GetCreditBatches { organization: Addr::unchecked("buyer"), start_after: None, limit: None };
+        let batches: CreditBatchesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(batches.batches, vec![CreditBatch { claim_id: 7, vintage: 2018, amount: Uint128::new(30) }]);
+        assert_eq!(batches.unattributed, Uint128::new(10));
+        let order: MarketOrder = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrder { id: 1 }).unwrap()).unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        let bid: MarketOrder = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrder { id: 2 }).unwrap()).unwrap();
+        assert_eq!(bid.status, OrderStatus::Open);
+        assert_eq!(bid.remaining, Uint128::new(10));
+
+        // A crossing ask partially fills the resting bid at the bid's price
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "inj".to_string(), price: Uint128::new(9), amount: Uint128::new(4) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg).unwrap();
+        assert_eq!(res.messages, vec![send("seller", 44)]);
+        assert_eq!(credits(&deps, "buyer"), Uint128::new(44));
+
+        let cancel_msg = ExecuteMsg::CancelOrder { order_id: 2 };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), cancel_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), cancel_msg.clone()).unwrap();
+        assert_eq!(res.messages, vec![send("buyer", 66)]);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &[]), cancel_msg);
+        assert_eq!(res.unwrap_err(), ContractError::OrderNotOpen {});
+
+        // Cancelling an ask returns the escrowed credits to the seller
+        execute(deps.as_mut(), env.clone(), seller_info, ExecuteMsg::CancelOrder { order_id: 0 }).unwrap();
+        assert_eq!(credits(&deps, "seller"), Uint128::new(56));
+        let query_msg = QueryMsg::GetOrderBook { denom: "inj".to_string(), side: OrderSide::Ask, start_after: None, limit: None };
+        let book: OrderBookResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert!(book.orders.is_empty());
+    }
+
+    #[test]
+    fn loans_accrue_interest() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (address, credits) in [("lender", 1000u128), ("borrower", 50u128)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::zero(),
+                carbon_credits: Uint128::new(credits),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: address.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(address), &org_info).unwrap();
+        }
+        let borrower_info = mock_info("borrower", &[]);
+        let lender_info = mock_info("lender", &[]);
+
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: None, term: 0, proof: None };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), request_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidLoanTerm { max: 31536000 });
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: None, term: 31536000, proof: None };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), request_msg).unwrap();
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::percent(10)), term: 31536000, proof: None };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), request_msg).unwrap();
+
+        for lend_request_id in [0, 1] {
+            prove_lend_request(deps.as_mut(), &env, lend_request_id);
+        }
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 0, response: "accepted".to_string(), interest_rate: None };
+        let res = execute(deps.as_mut(), env.clone(), lender_info.clone(), lend_msg);
+        assert_eq!(res.unwrap_err(), ContractError::MissingInterestRate {});
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 1, response: "accepted".to_string(), interest_rate: Some(Decimal::percent(12)) };
+        let res = execute(deps.as_mut(), env.clone(), lender_info.clone(), lend_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InterestRateConflict {});
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 0, response: "accepted".to_string(), interest_rate: Some(Decimal::percent(20)) };
+        execute(deps.as_mut(), env.clone(), lender_info, lend_msg).unwrap();
+        let maturity = env.block.time.seconds() + 31536000;
+
+        // Half a year at 20% accrues 10 on 100
+        env.block.time = env.block.time.plus_seconds(31536000 / 2);
+        let query_msg = QueryMsg::UserLendRequests { user: "borrower".to_string(), start_after: None, limit: None };
+        let loans: UserLendRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(loans.lend_requests[0].interest_rate, Some(Decimal::percent(20)));
+        assert_eq!(loans.lend_requests[0].maturity, Some(maturity));
+        assert_eq!(loans.lend_requests[0].outstanding_principal, Uint128::new(100));
+        assert_eq!(loans.lend_requests[0].accrued_interest, Uint128::new(10));
+
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(15) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg).unwrap();
+        assert!(res.attributes.contains(&cosmwasm_std::Attribute::new("principal", "5")));
+        assert!(res.attributes.contains(&cosmwasm_std::Attribute::new("interest", "10")));
+        let query_msg = QueryMsg::GetOrganization { address: Addr::unchecked("borrower") };
+        let borrower_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(borrower_res.carbon_credits, Uint128::new(135));
+        assert_eq!(borrower_res.debt, Uint128::new(95));
+        assert_eq!(borrower_res.total_returned, Uint128::new(5));
+        assert_eq!(borrower_res.interest_owed, Uint128::zero());
+        assert_eq!(borrower_res.total_interest_paid, Uint128::new(10));
+
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(96) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RepaymentExceedsDebt {});
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(95) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("lender", &[]), repay_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg.clone()).unwrap();
+        let query_msg = QueryMsg::UserLendRequests { user: "borrower".to_string(), start_after: None, limit: None };
+        let loans: UserLendRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(loans.lend_requests[0].status, LentStatus::Repaid);
+        assert_eq!(loans.lend_requests[0].outstanding_principal, Uint128::zero());
+        let res = execute(deps.as_mut(), env.clone(), borrower_info, repay_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RequestNotActive {});
+        let query_msg = QueryMsg::GetOrganization { address: Addr::unchecked("lender") };
+        let lender_res: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(lender_res.carbon_credits, Uint128::new(1010));
+    }
+
+    #[test]
+    fn defaulted_loans_seize_issuance() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            loan_grace_period: 86400,
+            default_penalty: Uint128::new(5),
+            seize_defaulted_issuance: true,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (address, credits, reputation) in [("lender", 1000u128, 0u128), ("borrower", 0u128, 10u128)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::new(credits),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: address.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(address), &org_info).unwrap();
+        }
+        let borrower_info = mock_info("borrower", &[]);
+        let lender_info = mock_info("lender", &[]);
+        let organization = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, address: &str| -> OrganizationResponse {
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOrganization { address: Addr::unchecked(address) }).unwrap()).unwrap()
+        };
+
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::zero()), term: 31536001, proof: None };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), request_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidLoanTerm { max: 31536000 });
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::zero()), term: 1000, proof: None };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), request_msg).unwrap();
+        prove_lend_request(deps.as_mut(), &env, 0);
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 0, response: "accepted".to_string(), interest_rate: None };
+        execute(deps.as_mut(), env.clone(), lender_info.clone(), lend_msg).unwrap();
+
+        let default_msg = ExecuteMsg::MarkDefault { loan_id: 0 };
+        let res = execute(deps.as_mut(), env.clone(), lender_info.clone(), default_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::LoanNotOverdue {});
+
+        // Past maturity only the lender may call the default until the grace period ends
+        env.block.time = env.block.time.plus_seconds(1001);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), default_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::LoanNotOverdue {});
+        execute(deps.as_mut(), env.clone(), lender_info.clone(), default_msg.clone()).unwrap();
+        assert_eq!(organization(&deps, "borrower").reputation_score, Uint128::new(5));
+        let res = execute(deps.as_mut(), env.clone(), lender_info, default_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RequestNotActive {});
+
+        // Defaulted loans can still be repaid directly
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(30) };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg).unwrap();
+
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), create_claim_msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") }).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let res = execute(deps.as_mut(), env.clone(), borrower_info, ExecuteMsg::FinalizeVoting { claim_id: 0 }).unwrap();
+
+        // The remaining 70 of the issuance goes to the lender, the rest to the claimant
+        assert!(res.events.iter().any(|event| event.ty == "issuance_seized"));
+        assert_eq!(organization(&deps, "lender").carbon_credits, Uint128::new(1000));
+        let borrower_res = organization(&deps, "borrower");
+        assert_eq!(borrower_res.carbon_credits, Uint128::new(100));
+        assert_eq!(borrower_res.debt, Uint128::zero());
+        let query_msg = QueryMsg::GetCreditBatches { organization: Addr::unchecked("lender"), start_after: None, limit: None };
+        let batches: CreditBatchesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(batches.batches[0].claim_id, 0);
+        assert_eq!(batches.batches[0].amount, Uint128::new(70));
+        let query_msg = QueryMsg::UserLendRequests { user: "borrower".to_string(), start_after: None, limit: None };
+        let loans: UserLendRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(loans.lend_requests[0].status, LentStatus::Repaid);
+        assert!(loans.lend_requests[0].defaulted_at.is_some());
+    }
+
+    #[test]
+    fn collateralized_loans_escrow_funds() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            collateral: Some(CollateralConfig { denom: "inj".to_string(), ratio: Decimal::percent(200) }),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (address, credits, reputation) in [("lender", 1000u128, 0u128), ("borrower", 0u128, 10u128)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::new(credits),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: address.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(address), &org_info).unwrap();
+        }
+        let lender_info = mock_info("lender", &[]);
+        let send = |to: &str, amount: u128| -> SubMsg {
+            SubMsg::new(BankMsg::Send { to_address: to.to_string(), amount: coins(amount, "inj") })
+        };
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::zero()), term: 1000, proof: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), request_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientCollateral { required: "200inj".to_string() });
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &coins(150, "inj")), request_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientCollateral { required: "200inj".to_string() });
+        for _ in 0..2 {
+            execute(deps.as_mut(), env.clone(), mock_info("borrower", &coins(200, "inj")), request_msg.clone()).unwrap();
+        }
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::zero()), term: 1000, proof: None };
+        execute(deps.as_mut(), env.clone(), mock_info("borrower", &coins(300, "inj")), request_msg.clone()).unwrap();
+
+        // Denied requests return the collateral, repaid loans release it
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 1, response: "denied".to_string(), interest_rate: None };
+        let res = execute(deps.as_mut(), env.clone(), lender_info.clone(), lend_msg).unwrap();
+        assert_eq!(res.messages, vec![send("borrower", 200)]);
+        for lend_request_id in [0, 2] {
+            prove_lend_request(deps.as_mut(), &env, lend_request_id);
+            let lend_msg = ExecuteMsg::LendTokens { lend_request_id, response: "accepted".to_string(), interest_rate: None };
+            let res = execute(deps.as_mut(), env.clone(), lender_info.clone(), lend_msg).unwrap();
+            assert!(res.messages.is_empty());
+        }
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(40) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), repay_msg).unwrap();
+        assert!(res.messages.is_empty());
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(60) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), repay_msg).unwrap();
+        assert_eq!(res.messages, vec![send("borrower", 200)]);
+
+        // Borrowers can withdraw requests the lender has not answered and recover their collateral
+        execute(deps.as_mut(), env.clone(), mock_info("borrower", &coins(200, "inj")), request_msg.clone()).unwrap();
+        let cancel_msg = ExecuteMsg::CancelLendRequest { lend_request_id: 3 };
+        let res = execute(deps.as_mut(), env.clone(), lender_info.clone(), cancel_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), cancel_msg.clone()).unwrap();
+        assert_eq!(res.messages, vec![send("borrower", 200)]);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), cancel_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RequestNotActive {});
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 3, response: "accepted".to_string(), interest_rate: None };
+        let res = execute(deps.as_mut(), env.clone(), lender_info.clone(), lend_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RequestNotActive {});
+        let cancel_msg = ExecuteMsg::CancelLendRequest { lend_request_id: 2 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), cancel_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RequestNotActive {});
+
+        // A default hands the collateral to the lender
+        env.block.time = env.block.time.plus_seconds(1001);
+        let res = execute(deps.as_mut(), env.clone(), lender_info, ExecuteMsg::MarkDefault { loan_id: 2 }).unwrap();
+        assert_eq!(res.messages, vec![send("lender", 300)]);
+        let query_msg = QueryMsg::UserLendRequests { user: "borrower".to_string(), start_after: None, limit: None };
+        let loans: UserLendRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert!(loans.lend_requests.iter().all(|loan| loan.collateral.is_none()));
+
+        let collateral_msg = ExecuteMsg::UpdateCollateralConfig { collateral: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), collateral_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), info, collateral_msg).unwrap();
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::zero()), term: 1000, proof: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &coins(200, "inj")), request_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::UnexpectedFunds {});
+        execute(deps.as_mut(), env, mock_info("borrower", &[]), request_msg).unwrap();
+    }
+
+    #[test]
+    fn pools_lend_and_share_repayments() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (address, credits, reputation) in [("alice", 1000u128, 0u128), ("bob", 1000u128, 0u128), ("borrower", 50u128, 1u128)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::new(credits),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: address.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(address), &org_info).unwrap();
+        }
+        let borrower_info = mock_info("borrower", &[]);
+
+        let create_msg = ExecuteMsg::CreatePool { min_eligibility_score: Uint128::new(1), base_rate: Decimal::percent(10), rate_slope: Decimal::percent(20), term: 31536000 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), create_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), info.clone(), create_msg).unwrap();
+        let create_msg = ExecuteMsg::CreatePool { min_eligibility_score: Uint128::new(1000), base_rate: Decimal::percent(10), rate_slope: Decimal::percent(20), term: 31536000 };
+        execute(deps.as_mut(), env.clone(), info, create_msg).unwrap();
+
+        for (address, amount) in [("alice", 600u128), ("bob", 400u128)] {
+            for pool_id in [0, 1] {
+                let deposit_msg = ExecuteMsg::DepositToPool { pool_id, amount: Uint128::new(amount / 2) };
+                execute(deps.as_mut(), env.clone(), mock_info(address, &[]), deposit_msg).unwrap();
+            }
+            let deposit_msg = ExecuteMsg::DepositToPool { pool_id: 0, amount: Uint128::new(amount / 2) };
+            execute(deps.as_mut(), env.clone(), mock_info(address, &[]), deposit_msg).unwrap();
+        }
+        let deposit_msg = ExecuteMsg::DepositToPool { pool_id: 0, amount: Uint128::new(1000) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), deposit_msg);
+        assert_eq!(res.unwrap_err(), ContractError::NotEnoughCredits {});
+
+        // The borrower proves its committed figures against the amount drawn and the pool's minimum score
+        let (proving_key, verifying_key) = proof_keys();
+        let update_msg = ExecuteMsg::UpdateVerifyingKey { verifying_key: Binary::from(verifying_key.clone()) };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update_msg).unwrap();
+        let borrow_msg = ExecuteMsg::BorrowFromPool { pool_id: 0, amount: Uint128::new(500), proof: Binary::from(vec![0u8; 128]) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), borrow_msg);
+        assert_eq!(res.unwrap_err(), ContractError::CommitmentNotSet {});
+        let borrower = commit_figures(deps.as_mut(), &env, &Addr::unchecked("borrower"));
+        let inputs = EligibilityInputs { borrower, salt: SALT, lender_credits: 500, lender_debt: 0, threshold: 1 };
+        let (_, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(0)).unwrap();
+
+        // Pool 1 requires a higher eligibility score than the borrower has
+        let pool_inputs = EligibilityInputs { lender_credits: 100, threshold: 1000, ..inputs };
+        let res = eligibility_proof(proving_key, &pool_inputs, &mut StdRng::seed_from_u64(0));
+        assert_eq!(res.unwrap_err(), ProofError::ThresholdNotMet);
+        let borrow_msg = ExecuteMsg::BorrowFromPool { pool_id: 1, amount: Uint128::new(100), proof: Binary::from(proof.clone()) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), borrow_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidProof {});
+        let borrow_msg = ExecuteMsg::BorrowFromPool { pool_id: 0, amount: Uint128::new(1001), proof: Binary::from(proof.clone()) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), borrow_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientPoolLiquidity {});
+        // The proof covers the amount drawn, not any other
+        let borrow_msg = ExecuteMsg::BorrowFromPool { pool_id: 0, amount: Uint128::new(400), proof: Binary::from(proof.clone()) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), borrow_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidProof {});
+        let borrow_msg = ExecuteMsg::BorrowFromPool { pool_id: 0, amount: Uint128::new(500), proof: Binary::from(proof) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), borrow_msg).unwrap();
+        assert!(res.attributes.contains(&cosmwasm_std::Attribute::new("interest_rate", "0.2")));
+
+        let pool: PoolResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetPool { pool_id: 0 }).unwrap()).unwrap();
+        assert_eq!(pool.liquidity, Uint128::new(500));
+        assert_eq!(pool.borrowed, Uint128::new(500));
+        assert_eq!(pool.total_shares, Uint128::new(1000));
+        assert_eq!(pool.utilization, Decimal::percent(50));
+        assert_eq!(pool.borrow_rate, Decimal::percent(20));
+        let query_msg = QueryMsg::UserLendRequests { user: "borrower".to_string(), start_after: None, limit: None };
+        let loans: UserLendRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(loans.lend_requests[0].lender, env.contract.address);
+        assert_eq!(loans.lend_requests[0].status, LentStatus::Approved);
+        assert_eq!(loans.lend_requests[0].interest_rate, Some(Decimal::percent(20)));
+
+        let withdraw_msg = ExecuteMsg::WithdrawFromPool { pool_id: 0, shares: Uint128::new(601) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), withdraw_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientShares {});
+        let withdraw_msg = ExecuteMsg::WithdrawFromPool { pool_id: 0, shares: Uint128::new(600) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), withdraw_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientPoolLiquidity {});
+
+        // Half a year at 20% earns the pool 50, shared pro rata
+        env.block.time = env.block.time.plus_seconds(31536000 / 2);
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(550) };
+        execute(deps.as_mut(), env.clone(), borrower_info, repay_msg).unwrap();
+        let pool: PoolResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetPool { pool_id: 0 }).unwrap()).unwrap();
+        assert_eq!(pool.liquidity, Uint128::new(1050));
+        assert_eq!(pool.borrowed, Uint128::zero());
+        let query_msg = QueryMsg::GetPoolShares { pool_id: 0, address: Addr::unchecked("bob") };
+        let shares: PoolSharesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(shares.shares, Uint128::new(400));
+        assert_eq!(shares.value, Uint128::new(420));
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), withdraw_msg).unwrap();
+        assert!(res.attributes.contains(&cosmwasm_std::Attribute::new("amount", "630")));
+        let query_msg = QueryMsg::GetOrganization { address: Addr::unchecked("alice") };
+        let alice: OrganizationResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(alice.carbon_credits, Uint128::new(730));
+    }
+
+    #[test]
+    fn loans_require_verified_proofs() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (address, credits, reputation) in [("lender", 1000u128, 0u128), ("borrower", 10u128, 2u128)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::new(credits),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: address.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(address), &org_info).unwrap();
+        }
+        let borrower_info = mock_info("borrower", &[]);
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::percent(10)), term: 31536000, proof: None };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), request_msg).unwrap();
+        let lend_msg = ExecuteMsg::LendTokens { lend_request_id: 0, response: "accepted".to_string(), interest_rate: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("lender", &[]), lend_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::ProofNotVerified {});
+
+        let (proving_key, verifying_key) = proof_keys();
+        let figures = BorrowerFigures { credits: 10, reputation: 2, ..BorrowerFigures::default() };
+        let inputs = EligibilityInputs { borrower: figures, salt: SALT, lender_credits: 1000, lender_debt: 0, threshold: 0 };
+        let public_inputs: Vec<Binary> = inputs.public_inputs().into_iter().map(Binary::from).collect();
+        let (score, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(score, 2 * (10 + 1000));
+        let verify_msg = ExecuteMsg::VerifyProof { lend_request_id: 0, proof: Binary::from(proof), public_inputs: public_inputs.clone() };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), verify_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::CommitmentNotSet {});
+        for commitment in [vec![1u8; 31], vec![0xffu8; 32]] {
+            let commit_msg = ExecuteMsg::CommitEligibility { commitment: Binary::from(commitment) };
+            let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), commit_msg);
+            assert_eq!(res.unwrap_err(), ContractError::InvalidEligibilityCommitment {});
+        }
+        // The salt stays with the borrower, the contract only sees the commitment
+        let commitment = Binary::from(figures.commitment(&SALT));
+        let commit_msg = ExecuteMsg::CommitEligibility { commitment: commitment.clone() };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), commit_msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), verify_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::CommitmentNotAttested {});
+
+        // Only the owner attests, and only the commitment it checked the figures behind
+        let attest_msg = ExecuteMsg::AttestEligibility { borrower: Addr::unchecked("borrower"), commitment: commitment.clone() };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), attest_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let inflated_commitment = Binary::from(BorrowerFigures { reputation: 100, ..figures }.commitment(&SALT));
+        let wrong_msg = ExecuteMsg::AttestEligibility { borrower: Addr::unchecked("borrower"), commitment: inflated_commitment.clone() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), wrong_msg);
+        assert_eq!(res.unwrap_err(), ContractError::CommitmentMismatch {});
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::AttestEligibility { borrower: Addr::unchecked("lender"), commitment: commitment.clone() });
+        assert_eq!(res.unwrap_err(), ContractError::CommitmentNotSet {});
+        execute(deps.as_mut(), env.clone(), info.clone(), attest_msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), verify_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::VerifyingKeyNotSet {});
+        let update_msg = ExecuteMsg::UpdateVerifyingKey { verifying_key: Binary::from(verifying_key.clone()) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), update_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        for verifying_key in [vec![], verifying_key[1..].to_vec()] {
+            let invalid_msg = ExecuteMsg::UpdateVerifyingKey { verifying_key: Binary::from(verifying_key) };
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), invalid_msg);
+            assert_eq!(res.unwrap_err(), ContractError::InvalidVerifyingKey {});
+        }
+        execute(deps.as_mut(), env.clone(), info.clone(), update_msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("lender", &[]), verify_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        // A proof of better figures than the attested ones is rejected either way it is submitted
+        let inflated = EligibilityInputs { borrower: BorrowerFigures { reputation: 100, ..figures }, ..inputs };
+        let (_, inflated_proof) = eligibility_proof(proving_key, &inflated, &mut StdRng::seed_from_u64(0)).unwrap();
+        let inflated_inputs = inflated.public_inputs().into_iter().map(Binary::from).collect();
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), ExecuteMsg::VerifyProof {
+            lend_request_id: 0,
+            proof: Binary::from(inflated_proof.clone()),
+            public_inputs: inflated_inputs,
+        });
+        assert_eq!(res.unwrap_err(), ContractError::ProofInputsMismatch {});
+        for proof in [inflated_proof, vec![0u8; 128]] {
+            let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), ExecuteMsg::VerifyProof {
+                lend_request_id: 0,
+                proof: Binary::from(proof),
+                public_inputs: public_inputs.clone(),
+            });
+            assert_eq!(res.unwrap_err(), ContractError::InvalidProof {});
+        }
+        // Committing to other figures withdraws the attestation until the owner checks them
+        let commit_inflated_msg = ExecuteMsg::CommitEligibility { commitment: inflated_commitment };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), commit_inflated_msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), verify_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::CommitmentNotAttested {});
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), commit_msg.clone()).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), attest_msg.clone()).unwrap();
+
+        // Raising the minimum score turns away proofs of a lower threshold
+        let config_msg = ExecuteMsg::UpdateLoanConfig {
+            max_loan_term: None,
+            loan_grace_period: None,
+            default_penalty: None,
+            seize_defaulted_issuance: None,
+            min_eligibility_score: Some(Uint128::new(2000)),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), config_msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), verify_msg);
+        assert_eq!(res.unwrap_err(), ContractError::BorrowerNotEligible {});
+        let res = eligibility_proof(proving_key, &EligibilityInputs { threshold: 2021, ..inputs }, &mut StdRng::seed_from_u64(0));
+        assert_eq!(res.unwrap_err(), ProofError::ThresholdNotMet);
+
+        // The threshold the borrower proves is recorded, never the score itself
+        let claimed = EligibilityInputs { threshold: 2010, ..inputs };
+        let (_, claimed_proof) = eligibility_proof(proving_key, &claimed, &mut StdRng::seed_from_u64(0)).unwrap();
+        let public_inputs = claimed.public_inputs().into_iter().map(Binary::from).collect();
+        let verify_msg = ExecuteMsg::VerifyProof { lend_request_id: 0, proof: Binary::from(claimed_proof.clone()), public_inputs };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), verify_msg).unwrap();
+        assert!(res.attributes.contains(&cosmwasm_std::Attribute::new("eligibility_score", "2010")));
+        let lend_request = LEND_REQUESTS.load(deps.as_ref().storage, 0).unwrap();
+        assert_eq!(lend_request.eligibility_score, Uint128::new(2010));
+        assert_eq!(lend_request.proof_data, hex::encode(&claimed_proof));
+        let inputs = EligibilityInputs { threshold: 2000, ..inputs };
+        let (_, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(0)).unwrap();
+
+        // The attestation backed that proof alone
+        let public_inputs = inputs.public_inputs().into_iter().map(Binary::from).collect();
+        let verify_msg = ExecuteMsg::VerifyProof { lend_request_id: 0, proof: Binary::from(proof.clone()), public_inputs };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), verify_msg);
+        assert_eq!(res.unwrap_err(), ContractError::CommitmentNotSet {});
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), commit_msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), attest_msg).unwrap();
+
+        // A proof can come with the request itself
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::percent(10)), term: 31536000, proof: Some(Binary::from(vec![0u8; 128])) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), request_msg);
+        assert_eq!(res.unwrap_err(), ContractError::InvalidProof {});
+        let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("lender"), amount: Uint128::new(100), interest_rate: Some(Decimal::percent(10)), term: 31536000, proof: Some(Binary::from(proof)) };
+        execute(deps.as_mut(), env.clone(), borrower_info, request_msg).unwrap();
+        for lend_request_id in [0, 1] {
+            let lend_msg = ExecuteMsg::LendTokens { lend_request_id, response: "accepted".to_string(), interest_rate: None };
+            execute(deps.as_mut(), env.clone(), mock_info("lender", &[]), lend_msg).unwrap();
+        }
+    }
+
+    #[test]
+    fn bonds_and_dispute_bonds_escrow_credit_tokens() {
+        let mut app = App::default();
+        let token_code_id = app.store_code(Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        let contract_code_id = app.store_code(Box::new(
+            ContractWrapper::new(execute, instantiate, query).with_reply(reply),
+        ));
+        let msg = InstantiateMsg {
+            bond_slash: Decimal::percent(50),
+            dispute_bond: Uint128::new(10),
+            credit_token: Some(CreditTokenInfo {
+                code_id: token_code_id,
+                name: "Carbon Credit".to_string(),
+                symbol: "CARBON".to_string(),
+                decimals: 0,
+            }),
+            ..default_instantiate_msg()
+        };
+        let contract = app
+            .instantiate_contract(contract_code_id, Addr::unchecked("creator"), &msg, &[], "carbon", None)
+            .unwrap();
+        let config_res: ConfigResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetConfig {}).unwrap();
+        let token = config_res.credit_token.unwrap();
+        let balance = |app: &App, address: &str| -> Uint128 {
+            let res: cw20::BalanceResponse = app.wrap()
+                .query_wasm_smart(&token, &cw20::Cw20QueryMsg::Balance { address: address.to_string() })
+                .unwrap();
+            res.balance
+        };
+        let credits = |app: &App, address: &str| -> Uint128 {
+            let res: OrganizationResponse = app.wrap()
+                .query_wasm_smart(&contract, &QueryMsg::GetOrganization { address: Addr::unchecked(address) })
+                .unwrap();
+            res.carbon_credits
+        };
+
+        for verifier in ["voter", "checker"] {
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(verifier) };
+            app.execute_contract(Addr::unchecked("creator"), contract.clone(), &add_verifier_msg, &[]).unwrap();
+        }
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        app.execute_contract(Addr::unchecked("voter"), contract.clone(), &create_claim_msg, &[]).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        app.execute_contract(Addr::unchecked("checker"), contract.clone(), &vote_msg, &[]).unwrap();
+        app.update_block(|block| block.time = block.time.plus_seconds(86401));
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "voter"), Uint128::new(100));
+
+        // Bonds are escrowed out of the credits the contract already holds
+        let bond_msg = ExecuteMsg::BondCredits { amount: Uint128::new(30) };
+        app.execute_contract(Addr::unchecked("voter"), contract.clone(), &bond_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "voter"), Uint128::new(70));
+        let unbond_msg = ExecuteMsg::UnbondCredits { amount: Uint128::new(10) };
+        app.execute_contract(Addr::unchecked("voter"), contract.clone(), &unbond_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "voter"), Uint128::new(80));
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(100));
+
+        // A slashed bond is burned out of escrow
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["124.456".to_string(), "124.466".to_string(), "124.466".to_string(), "124.456".to_string(), "124.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &create_claim_msg, &[]).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 1, vote: VoteOption::Yes };
+        app.execute_contract(Addr::unchecked("checker"), contract.clone(), &vote_msg, &[]).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 1, vote: VoteOption::No };
+        app.execute_contract(Addr::unchecked("voter"), contract.clone(), &vote_msg, &[]).unwrap();
+        app.update_block(|block| block.time = block.time.plus_seconds(86401));
+        let finalize_claim_msg = ExecuteMsg::FinalizeVoting { claim_id: 1 };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_claim_msg, &[]).unwrap();
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(190));
+        let total_res: TotalCarbonCreditsResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetTotalCarbonCredits {}).unwrap();
+        let supply: cw20::TokenInfoResponse = app.wrap().query_wasm_smart(&token, &cw20::Cw20QueryMsg::TokenInfo {}).unwrap();
+        assert_eq!(total_res.total, supply.total_supply);
+
+        // The dispute bond is escrowed and comes back when the dispute fails quorum
+        let dispute_msg = ExecuteMsg::DisputeClaim { claim_id: 1 };
+        app.execute_contract(Addr::unchecked("voter"), contract.clone(), &dispute_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "voter"), Uint128::new(70));
+        app.update_block(|block| block.time = block.time.plus_seconds(86401));
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_claim_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "voter"), Uint128::new(80));
+
+        // Removing a verifier hands back the escrowed bond, which can then be withdrawn
+        let remove_verifier_msg = ExecuteMsg::RemoveVerifier { address: Addr::unchecked("voter") };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &remove_verifier_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "voter"), Uint128::new(90));
+        let withdraw_msg = ExecuteMsg::WithdrawCredits { amount: Uint128::new(90) };
+        app.execute_contract(Addr::unchecked("voter"), contract.clone(), &withdraw_msg, &[]).unwrap();
+        assert_eq!(balance(&app, "voter"), Uint128::new(90));
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(100));
+        assert_eq!(credits(&app, "voter"), Uint128::zero());
+    }
+
+    #[test]
+    fn dispute_outcomes_burn_credit_tokens() {
+        let mut app = App::default();
+        let token_code_id = app.store_code(Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        let contract_code_id = app.store_code(Box::new(
+            ContractWrapper::new(execute, instantiate, query).with_reply(reply),
+        ));
+        let msg = InstantiateMsg {
+            dispute_bond: Uint128::new(10),
+            credit_token: Some(CreditTokenInfo {
+                code_id: token_code_id,
+                name: "Carbon Credit".to_string(),
+                symbol: "CARBON".to_string(),
+                decimals: 0,
+            }),
+            ..default_instantiate_msg()
+        };
+        let contract = app
+            .instantiate_contract(contract_code_id, Addr::unchecked("creator"), &msg, &[], "carbon", None)
+            .unwrap();
+        let config_res: ConfigResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetConfig {}).unwrap();
+        let token = config_res.credit_token.unwrap();
+        let balance = |app: &App, address: &str| -> Uint128 {
+            let res: cw20::BalanceResponse = app.wrap()
+                .query_wasm_smart(&token, &cw20::Cw20QueryMsg::Balance { address: address.to_string() })
+                .unwrap();
+            res.balance
+        };
+        let credits = |app: &App, address: &str| -> Uint128 {
+            let res: OrganizationResponse = app.wrap()
+                .query_wasm_smart(&contract, &QueryMsg::GetOrganization { address: Addr::unchecked(address) })
+                .unwrap();
+            res.carbon_credits
+        };
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("checker") };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &add_verifier_msg, &[]).unwrap();
+
+        // Claims 0 to 3, one per organization on separate parcels
+        for (claim_id, owner) in ["owner", "challenger", "owner2", "owner3"].iter().enumerate() {
+            let west = format!("{}.456", 123 + claim_id);
+            let east = format!("{}.466", 123 + claim_id);
+            let create_claim_msg = ExecuteMsg::CreateClaim {
+                longitudes: vec![west.clone(), east.clone(), east, west.clone(), west],
+                latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+                time_started: 1000,
+                time_ended: 2000,
+                demanded_tokens: Uint128::new(100),
+                ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+                methodology: "forestry".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(*owner), contract.clone(), &create_claim_msg, &[]).unwrap();
+            let vote_msg = ExecuteMsg::CastVote { claim_id: claim_id as u64, vote: VoteOption::Yes };
+            app.execute_contract(Addr::unchecked("checker"), contract.clone(), &vote_msg, &[]).unwrap();
+        }
+        app.update_block(|block| block.time = block.time.plus_seconds(86401));
+        for claim_id in 0..4 {
+            let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id };
+            app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_msg, &[]).unwrap();
+        }
+
+        for claim_id in [0, 2, 3] {
+            let dispute_msg = ExecuteMsg::DisputeClaim { claim_id };
+            app.execute_contract(Addr::unchecked("challenger"), contract.clone(), &dispute_msg, &[]).unwrap();
+        }
+        assert_eq!(credits(&app, "challenger"), Uint128::new(70));
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(400));
+        for (claim_id, vote) in [(0, VoteOption::No), (2, VoteOption::No), (3, VoteOption::Yes)] {
+            let vote_msg = ExecuteMsg::CastVote { claim_id, vote };
+            app.execute_contract(Addr::unchecked("checker"), contract.clone(), &vote_msg, &[]).unwrap();
+        }
+        app.update_block(|block| block.time = block.time.plus_seconds(86401));
+
+        // An overturned approval burns the clawed back credits the contract holds
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_msg, &[]).unwrap();
+        assert_eq!(credits(&app, "owner"), Uint128::zero());
+        assert_eq!(credits(&app, "challenger"), Uint128::new(80));
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(300));
+
+        // Credits withdrawn before the dispute resolves escape the clawback, the rest is burned
+        let withdraw_msg = ExecuteMsg::WithdrawCredits { amount: Uint128::new(60) };
+        app.execute_contract(Addr::unchecked("owner2"), contract.clone(), &withdraw_msg, &[]).unwrap();
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 2 };
+        let res = app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_msg, &[]).unwrap();
+        assert!(res.events.iter().any(|event| event.ty == "wasm-credits_burned"
+            && event.attributes.contains(&cosmwasm_std::Attribute::new("shortfall", "60"))));
+        let claim_res: ClaimResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetClaim { id: 2 }).unwrap();
+        assert_eq!(claim_res.status, ClaimStatus::Rejected);
+        assert_eq!(credits(&app, "owner2"), Uint128::zero());
+        assert_eq!(balance(&app, "owner2"), Uint128::new(60));
+        assert_eq!(credits(&app, "challenger"), Uint128::new(90));
+
+        // An upheld decision burns the escrowed dispute bond
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 3 };
+        app.execute_contract(Addr::unchecked("creator"), contract.clone(), &finalize_msg, &[]).unwrap();
+        let dispute_res: DisputeResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetDispute { claim_id: 3 }).unwrap();
+        assert_eq!(dispute_res.status, DisputeStatus::Upheld);
+        assert_eq!(credits(&app, "challenger"), Uint128::new(90));
+        assert_eq!(balance(&app, contract.as_str()), Uint128::new(190));
+        let supply: cw20::TokenInfoResponse = app.wrap().query_wasm_smart(&token, &cw20::Cw20QueryMsg::TokenInfo {}).unwrap();
+        assert_eq!(supply.total_supply, Uint128::new(250));
+        let total_res: TotalCarbonCreditsResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetTotalCarbonCredits {}).unwrap();
+        assert_eq!(total_res.total, Uint128::new(250));
+
+        // Overturned first rounds are judged again, an upheld one keeps its reward
+        let record_res: VotingRecordResponse = app.wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetVotingRecord { address: Addr::unchecked("checker"), start_after: None, limit: None })
+            .unwrap();
+        let gained: Vec<(u64, Uint128)> = record_res.records.iter().map(|record| (record.claim_id, record.reputation_gained)).collect();
+        assert_eq!(gained, vec![(0, Uint128::new(1)), (1, Uint128::new(1)), (2, Uint128::new(1)), (3, Uint128::new(2))]);
+        let checker_res: OrganizationResponse = app.wrap().query_wasm_smart(&contract, &QueryMsg::GetOrganization { address: Addr::unchecked("checker") }).unwrap();
+        assert_eq!(checker_res.reputation_score, Uint128::new(5));
+
+        // Refunded bonds return to the vintages they were taken from
+        let batches_res: CreditBatchesResponse = app.wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetCreditBatches { organization: Addr::unchecked("challenger"), start_after: None, limit: None })
+            .unwrap();
+        assert_eq!(batches_res.batches.len(), 1);
+        assert_eq!(batches_res.batches[0].claim_id, 1);
+        assert_eq!(batches_res.batches[0].amount, Uint128::new(90));
+        assert_eq!(batches_res.unattributed, Uint128::zero());
+    }
+
+    #[test]
+    fn cid_test_vectors() {
+        // The same dag-pb sha2-256 content in every supported encoding
+        let valid = [
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            "bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34",
+            "BAFYBEIE5NQV6KD3QNFJUPGVZ34WOH3OKSC3IAU6ABMYAJN7QVTF6D2HO34",
+            "zdj7Wg2Qkk4mYgAkVU1kppfQ2sMGz5zPwERVpeWmxCQLDxVoC",
+            "f017012209d6c2be50f706953479ab9df2ce3edca90b68053c00b3004b7f0accbe1e8eedf",
+        ];
+        for cid in valid {
+            assert_eq!(validate_cid(cid), Ok(()), "{}", cid);
+        }
+
+        let invalid = [
+            "",
+            // CIDv0 with a character outside base58 and with a truncated digest
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd",
+            // Unsupported multibase prefix
+            "mAXASIJ1sK+UPcGlTR5q53yzj7cqQtoBTwAswBLfwrMvh6O7f",
+            // Version 2
+            "bajybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34",
+            // Digest shorter than its header says
+            "bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho",
+            // Nonzero padding bits and a trailing character that carries no byte
+            "bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho35",
+            "bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34a",
+            // Characters outside the alphabet, odd length hex
+            "bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho18",
+            "f017012209d6c2be50f706953479ab9df2ce3edca90b68053c00b3004b7f0accbe1e8eed",
+        ];
+        for cid in invalid {
+            assert_eq!(validate_cid(cid), Err(ContractError::InvalidCid { cid: cid.to_string() }), "{}", cid);
+        }
+        let oversized = format!("f{}", "0".repeat(MAX_CID_LENGTH));
+        assert_eq!(validate_cid(&oversized), Err(ContractError::InvalidCid { cid: oversized.clone() }));
+    }
+
+    #[test]
+    fn credit_batches_follow_escrowed_credits() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            vote_reward: Uint128::zero(),
+            dispute_bond: Uint128::new(10),
+            dispute_min_voters: 2,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for verifier in ["voter", "holder"] {
+            let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked(verifier) };
+            execute(deps.as_mut(), env.clone(), info.clone(), add_verifier_msg).unwrap();
+        }
+
+        // Claims 0 and 1 for the holder end in 1970 and 2018, claim 2 belongs to someone else
+        let claims = [("holder", 1000, 2000), ("holder", 1_520_000_000, 1_530_000_000), ("other", 1_540_000_000, 1_550_000_000)];
+        for (claim_id, &(owner, time_started, time_ended)) in claims.iter().enumerate() {
+            let create_claim_msg = ExecuteMsg::CreateClaim {
+                longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+                latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+                time_started,
+                time_ended,
+                demanded_tokens: Uint128::new(50),
+                ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+                methodology: "forestry".to_string(),
+            };
+            execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), create_claim_msg).unwrap();
+            let vote_msg = ExecuteMsg::CastVote { claim_id: claim_id as u64, vote: VoteOption::Yes };
+            execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+        }
+        env.block.time = env.block.time.plus_seconds(86401);
+        for claim_id in 0..3 {
+            let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id };
+            execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+        }
+
+        // Every credit the holder has is attributed to a batch, whichever escrow it passed through
+        let assert_attributed = |deps: Deps, env: &Env, credits: u128| {
+            let query_msg = QueryMsg::GetCreditBatches { organization: Addr::unchecked("holder"), start_after: None, limit: None };
+            let batches: CreditBatchesResponse = from_binary(&query(deps, env.clone(), query_msg).unwrap()).unwrap();
+            let total: Uint128 = batches.batches.iter().map(|batch| batch.amount).sum();
+            assert_eq!(organization(deps, env, &Addr::unchecked("holder")).carbon_credits, Uint128::new(credits));
+            assert_eq!(total, Uint128::new(credits));
+            assert_eq!(batches.unattributed, Uint128::zero());
+            batches.batches
+        };
+        assert_attributed(deps.as_ref(), &env, 100);
+
+        let bond_msg = ExecuteMsg::BondCredits { amount: Uint128::new(60) };
+        execute(deps.as_mut(), env.clone(), mock_info("holder", &[]), bond_msg).unwrap();
+        let batches = assert_attributed(deps.as_ref(), &env, 40);
+        assert_eq!(batches, vec![CreditBatch { claim_id: 1, vintage: 2018, amount: Uint128::new(40) }]);
+        // Unbonding returns the oldest bonded vintages first
+        let unbond_msg = ExecuteMsg::UnbondCredits { amount: Uint128::new(20) };
+        execute(deps.as_mut(), env.clone(), mock_info("holder", &[]), unbond_msg).unwrap();
+        let batches = assert_attributed(deps.as_ref(), &env, 60);
+        assert_eq!(batches, vec![
+            CreditBatch { claim_id: 0, vintage: 1970, amount: Uint128::new(20) },
+            CreditBatch { claim_id: 1, vintage: 2018, amount: Uint128::new(40) },
+        ]);
+
+        // A dispute that fails quorum hands the bond back with its batches
+        let dispute_msg = ExecuteMsg::DisputeClaim { claim_id: 2 };
+        execute(deps.as_mut(), env.clone(), mock_info("holder", &[]), dispute_msg).unwrap();
+        assert_attributed(deps.as_ref(), &env, 50);
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 2 };
+        execute(deps.as_mut(), env.clone(), info.clone(), finalize_msg).unwrap();
+        let dispute_res: DisputeResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetDispute { claim_id: 2 }).unwrap()).unwrap();
+        assert_eq!(dispute_res.status, DisputeStatus::FailedQuorum);
+        assert_attributed(deps.as_ref(), &env, 60);
+
+        // Pool liquidity keeps the deposited batches
+        let create_msg = ExecuteMsg::CreatePool { min_eligibility_score: Uint128::zero(), base_rate: Decimal::percent(10), rate_slope: Decimal::percent(20), term: 31536000 };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_msg).unwrap();
+        let deposit_msg = ExecuteMsg::DepositToPool { pool_id: 0, amount: Uint128::new(30) };
+        execute(deps.as_mut(), env.clone(), mock_info("holder", &[]), deposit_msg).unwrap();
+        assert_attributed(deps.as_ref(), &env, 30);
+        let withdraw_msg = ExecuteMsg::WithdrawFromPool { pool_id: 0, shares: Uint128::new(30) };
+        execute(deps.as_mut(), env.clone(), mock_info("holder", &[]), withdraw_msg).unwrap();
+        assert_attributed(deps.as_ref(), &env, 60);
+
+        let remove_verifier_msg = ExecuteMsg::RemoveVerifier { address: Addr::unchecked("holder") };
+        execute(deps.as_mut(), env.clone(), info.clone(), remove_verifier_msg).unwrap();
+        let batches = assert_attributed(deps.as_ref(), &env, 100);
+        assert_eq!(batches, vec![
+            CreditBatch { claim_id: 0, vintage: 1970, amount: Uint128::new(50) },
+            CreditBatch { claim_id: 1, vintage: 2018, amount: Uint128::new(50) },
+        ]);
+    }
+
+    #[test]
+    fn order_matching_is_bounded_and_quoted() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            quote_denoms: vec!["inj".to_string(), "usdt".to_string()],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let org_info = crate::state::OrganizationInfo {
+            reputation_score: Uint128::zero(),
+            carbon_credits: Uint128::new(100),
+            debt: Uint128::zero(),
+            times_borrowed: 0,
+            total_borrowed: Uint128::zero(),
+            total_returned: Uint128::zero(),
+            name: "seller".to_string(),
+            emissions: Uint128::zero(),
+            interest_owed: Uint128::zero(),
+            total_interest_paid: Uint128::zero(),
+        };
+        ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked("seller"), &org_info).unwrap();
+        let seller_info = mock_info("seller", &[]);
+        let credits = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, address: &str| -> Uint128 {
+            let res: OrganizationResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOrganization { address: Addr::unchecked(address) }).unwrap()).unwrap();
+            res.carbon_credits
+        };
+        let book = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, denom: &str, side: OrderSide| -> Vec<u64> {
+            let query_msg = QueryMsg::GetOrderBook { denom: denom.to_string(), side, start_after: None, limit: Some(100) };
+            let res: OrderBookResponse = from_binary(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+            res.orders.iter().map(|order| order.id).collect()
+        };
+
+        // Orders are only quoted in the configured native denoms
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "uatom".to_string(), price: Uint128::new(10), amount: Uint128::new(1) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg);
+        assert_eq!(res.unwrap_err(), ContractError::UnsupportedDenom { denom: "uatom".to_string() });
+        let bid_msg = ExecuteMsg::PlaceBid { price: Uint128::new(10), amount: Uint128::new(1) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(10, "uatom")), bid_msg);
+        assert_eq!(res.unwrap_err(), ContractError::UnsupportedDenom { denom: "uatom".to_string() });
+
+        // A bid crossing more asks than one placement may fill gets the rest of its payment back
+        for _ in 0..MAX_ORDER_MATCHES + 1 {
+            let ask_msg = ExecuteMsg::PlaceAsk { denom: "usdt".to_string(), price: Uint128::new(10), amount: Uint128::new(1) };
+            execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg).unwrap();
+        }
+        let bid_msg = ExecuteMsg::PlaceBid { price: Uint128::new(10), amount: Uint128::new(40) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(400, "usdt")), bid_msg).unwrap();
+        assert_eq!(res.events.len(), MAX_ORDER_MATCHES);
+        assert_eq!(res.messages.last(), Some(&SubMsg::new(BankMsg::Send { to_address: "buyer".to_string(), amount: coins(100, "usdt") })));
+        assert_eq!(credits(&deps, "buyer"), Uint128::new(30));
+        let bid: MarketOrder = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrder { id: 31 }).unwrap()).unwrap();
+        assert_eq!(bid.status, OrderStatus::Cancelled);
+        assert_eq!(bid.remaining, Uint128::new(10));
+        assert!(book(&deps, "usdt", OrderSide::Bid).is_empty());
+        assert_eq!(book(&deps, "usdt", OrderSide::Ask), vec![30]);
+
+        // Likewise an ask gets its unmatched credits back
+        for _ in 0..MAX_ORDER_MATCHES + 1 {
+            let bid_msg = ExecuteMsg::PlaceBid { price: Uint128::new(10), amount: Uint128::new(1) };
+            execute(deps.as_mut(), env.clone(), mock_info("buyer", &coins(10, "inj")), bid_msg).unwrap();
+        }
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "inj".to_string(), price: Uint128::new(10), amount: Uint128::new(40) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), ask_msg).unwrap();
+        assert_eq!(res.events.len(), MAX_ORDER_MATCHES);
+        assert_eq!(credits(&deps, "seller"), Uint128::new(100 - 31 - 30));
+        let ask: MarketOrder = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetOrder { id: 63 }).unwrap()).unwrap();
+        assert_eq!(ask.status, OrderStatus::Cancelled);
+        assert!(book(&deps, "inj", OrderSide::Ask).is_empty());
+        assert_eq!(book(&deps, "inj", OrderSide::Bid), vec![62]);
+
+        // Only the owner picks the quote denoms
+        let update_msg = ExecuteMsg::UpdateMarketConfig { quote_denoms: vec!["inj".to_string()] };
+        let res = execute(deps.as_mut(), env.clone(), seller_info.clone(), update_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), info, update_msg).unwrap();
+        let ask_msg = ExecuteMsg::PlaceAsk { denom: "usdt".to_string(), price: Uint128::new(10), amount: Uint128::new(1) };
+        let res = execute(deps.as_mut(), env.clone(), seller_info, ask_msg);
+        assert_eq!(res.unwrap_err(), ContractError::UnsupportedDenom { denom: "usdt".to_string() });
+    }
+
+    #[test]
+    fn repayments_apply_to_the_given_loan() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator_info = mock_info("creator", &[]);
+        let msg = default_instantiate_msg();
+        instantiate(deps.as_mut(), env.clone(), creator_info.clone(), msg).unwrap();
+        let add_verifier_msg = ExecuteMsg::AddVerifier { address: Addr::unchecked("voter") };
+        execute(deps.as_mut(), env.clone(), creator_info.clone(), add_verifier_msg).unwrap();
+        let create_claim_msg = ExecuteMsg::CreateClaim {
+            longitudes: vec!["123.456".to_string(), "123.466".to_string(), "123.466".to_string(), "123.456".to_string(), "123.456".to_string()],
+            latitudes: vec!["78.90".to_string(), "78.90".to_string(), "78.91".to_string(), "78.91".to_string(), "78.90".to_string()],
+            time_started: 1000,
+            time_ended: 2000,
+            demanded_tokens: Uint128::new(100),
+            ipfs_hashes: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            methodology: "forestry".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), creator_info.clone(), create_claim_msg).unwrap();
+        let vote_msg = ExecuteMsg::CastVote { claim_id: 0, vote: VoteOption::Yes };
+        execute(deps.as_mut(), env.clone(), mock_info("voter", &[]), vote_msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(86401);
+        let finalize_msg = ExecuteMsg::FinalizeVoting { claim_id: 0 };
+        execute(deps.as_mut(), env.clone(), creator_info.clone(), finalize_msg).unwrap();
+
+        // Loans 0 and 1 go to the borrower, loan 2 to someone else, all interest free
+        for (lend_request_id, (borrower, amount)) in vec![("borrower", 20), ("borrower", 30), ("other", 10)].into_iter().enumerate() {
+            let request_msg = ExecuteMsg::CreateLendToken { lender: Addr::unchecked("creator"), amount: Uint128::new(amount), interest_rate: Some(Decimal::zero()), term: 31536000, proof: None };
+            execute(deps.as_mut(), env.clone(), mock_info(borrower, &[]), request_msg).unwrap();
+            prove_lend_request(deps.as_mut(), &env, lend_request_id as u64);
+        }
+        for lend_request_id in 0..3 {
+            let lend_msg = ExecuteMsg::LendTokens { lend_request_id, response: "accepted".to_string(), interest_rate: None };
+            execute(deps.as_mut(), env.clone(), creator_info.clone(), lend_msg).unwrap();
+        }
+        let loan = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, user: &str, id: u64| -> LendRequestResponse {
+            let query_msg = QueryMsg::UserLendRequests { user: user.to_string(), start_after: None, limit: None };
+            let loans: UserLendRequestsResponse = from_binary(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+            loans.lend_requests.into_iter().find(|loan| loan.id == id).unwrap()
+        };
+        let borrower_info = mock_info("borrower", &[]);
+
+        // A partial repayment only reduces the loan it names
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 1, amount: Uint128::new(10) };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg).unwrap();
+        let second = loan(&deps, "borrower", 1);
+        assert_eq!(second.outstanding_principal, Uint128::new(20));
+        assert_eq!(second.status, LentStatus::Approved);
+        assert_eq!(loan(&deps, "borrower", 0).outstanding_principal, Uint128::new(20));
+        let borrower_res = organization(deps.as_ref(), &env, &Addr::unchecked("borrower"));
+        assert_eq!(borrower_res.carbon_credits, Uint128::new(40));
+        assert_eq!(borrower_res.debt, Uint128::new(40));
+        assert_eq!(organization(deps.as_ref(), &env, &Addr::unchecked("creator")).carbon_credits, Uint128::new(50));
+
+        // Another borrower's loan cannot be repaid, even by an account holding enough credits
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 2, amount: Uint128::new(10) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg);
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        assert_eq!(loan(&deps, "other", 2).outstanding_principal, Uint128::new(10));
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 9, amount: Uint128::new(10) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RequestNotFound {});
+
+        // Paying more than the loan owes is rejected rather than spilling onto the other loan
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(21) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RepaymentExceedsDebt {});
+        assert_eq!(organization(deps.as_ref(), &env, &Addr::unchecked("borrower")).carbon_credits, Uint128::new(40));
+
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(20) };
+        execute(deps.as_mut(), env.clone(), borrower_info.clone(), repay_msg).unwrap();
+        let first = loan(&deps, "borrower", 0);
+        assert_eq!(first.status, LentStatus::Repaid);
+        assert_eq!(first.outstanding_principal, Uint128::zero());
+        assert_eq!(loan(&deps, "borrower", 1).outstanding_principal, Uint128::new(20));
+        let repay_msg = ExecuteMsg::RepayTokens { loan_id: 0, amount: Uint128::new(1) };
+        let res = execute(deps.as_mut(), env.clone(), borrower_info, repay_msg);
+        assert_eq!(res.unwrap_err(), ContractError::RequestNotActive {});
+    }
+
+
+    #[test]
+    fn pools_escrow_collateral_and_refuse_written_off_deposits() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            collateral: Some(CollateralConfig { denom: "inj".to_string(), ratio: Decimal::percent(200) }),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for (address, credits, reputation) in [("alice", 1000u128, 0u128), ("bob", 1000u128, 0u128), ("borrower", 0u128, 1u128)] {
+            let org_info = crate::state::OrganizationInfo {
+                reputation_score: Uint128::new(reputation),
+                carbon_credits: Uint128::new(credits),
+                debt: Uint128::zero(),
+                times_borrowed: 0,
+                total_borrowed: Uint128::zero(),
+                total_returned: Uint128::zero(),
+                name: address.to_string(),
+                emissions: Uint128::zero(),
+                interest_owed: Uint128::zero(),
+                total_interest_paid: Uint128::zero(),
+            };
+            ORGANIZATIONS.save(deps.as_mut().storage, &Addr::unchecked(address), &org_info).unwrap();
+        }
+        let create_msg = ExecuteMsg::CreatePool { min_eligibility_score: Uint128::new(1), base_rate: Decimal::percent(10), rate_slope: Decimal::percent(20), term: 1000 };
+        execute(deps.as_mut(), env.clone(), info.clone(), create_msg).unwrap();
+        let deposit_msg = ExecuteMsg::DepositToPool { pool_id: 0, amount: Uint128::new(100) };
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), deposit_msg).unwrap();
+
+        let (proving_key, verifying_key) = proof_keys();
+        let update_msg = ExecuteMsg::UpdateVerifyingKey { verifying_key: Binary::from(verifying_key.clone()) };
+        execute(deps.as_mut(), env.clone(), info, update_msg).unwrap();
+        let borrower = commit_figures(deps.as_mut(), &env, &Addr::unchecked("borrower"));
+        let inputs = EligibilityInputs { borrower, salt: SALT, lender_credits: 100, lender_debt: 0, threshold: 1 };
+        let (_, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(0)).unwrap();
+
+        // Pool loans are collateralized like lend requests
+        let borrow_msg = ExecuteMsg::BorrowFromPool { pool_id: 0, amount: Uint128::new(100), proof: Binary::from(proof) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), borrow_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientCollateral { required: "200inj".to_string() });
+        let res = execute(deps.as_mut(), env.clone(), mock_info("borrower", &coins(150, "inj")), borrow_msg.clone());
+        assert_eq!(res.unwrap_err(), ContractError::InsufficientCollateral { required: "200inj".to_string() });
+        execute(deps.as_mut(), env.clone(), mock_info("borrower", &coins(200, "inj")), borrow_msg).unwrap();
+        let query_msg = QueryMsg::UserLendRequests { user: "borrower".to_string(), start_after: None, limit: None };
+        let loans: UserLendRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(loans.lend_requests[0].collateral, Some(Coin::new(200, "inj")));
+
+        // The default writes the pool down to nothing and its forfeit goes to the owner
+        env.block.time = env.block.time.plus_seconds(1001);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), ExecuteMsg::MarkDefault { loan_id: 0 }).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(BankMsg::Send { to_address: "creator".to_string(), amount: coins(200, "inj") })]);
+        let pool: PoolResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetPool { pool_id: 0 }).unwrap()).unwrap();
+        assert_eq!(pool.liquidity + pool.borrowed, Uint128::zero());
+        assert_eq!(pool.total_shares, Uint128::new(100));
+
+        // New deposits would only buy into alice's worthless shares
+        let deposit_msg = ExecuteMsg::DepositToPool { pool_id: 0, amount: Uint128::new(100) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), deposit_msg);
+        assert_eq!(res.unwrap_err(), ContractError::PoolWrittenOff {});
+        let bob = organization(deps.as_ref(), &env, &Addr::unchecked("bob"));
+        assert_eq!(bob.carbon_credits, Uint128::new(1000));
+    }
 }
\ No newline at end of file
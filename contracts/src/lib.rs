@@ -1,5 +1,8 @@
+pub mod cid;
 pub mod contract;
 mod error;
+pub mod geometry;
+#[allow(unused_imports)]
 pub mod helpers;
 pub mod integration_tests;
 pub mod msg;
@@ -1,12 +1,57 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{ClaimStatus, OrganizationInfo, VoteOption};
+use crate::geometry::Polygon;
+use crate::state::{ClaimStatus, CollateralConfig, CreditBatch, DisputeStatus, MarketOrder, Methodology, OrderSide, RetirementCertificate, VoteOption, VoteRecord, VotingPowerModel};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub voting_period: u64, // in seconds
+    pub min_voters: u64,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_power: VotingPowerModel,
+    pub verifier_min_reputation: Uint128,
+    pub verifier_min_bond: Uint128,
+    pub vote_reward: Uint128,
+    pub vote_slash: Uint128,
+    pub bond_slash: Decimal,
+    pub commit_reveal: bool,
+    pub reveal_period: u64, // in seconds
+    pub unrevealed_slash: Uint128,
+    pub challenge_period: u64, // in seconds
+    pub dispute_bond: Uint128,
+    pub dispute_min_voters: u64,
+    pub dispute_quorum: Decimal,
+    pub max_claim_period: u64,
+    pub max_loan_term: u64, // in seconds
+    pub loan_grace_period: u64, // in seconds
+    pub default_penalty: Uint128,
+    pub seize_defaulted_issuance: bool,
+    pub min_eligibility_score: Uint128,
+    pub collateral: Option<CollateralConfig>,
+    pub quote_denoms: Vec<String>, // native denoms orders may be priced in, e.g. inj and usdt
+    pub methodologies: Vec<Methodology>,
+    pub credit_token: Option<CreditTokenInfo>,
+}
+
+/// CW20 code the contract instantiates, as minter, to back carbon credits.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreditTokenInfo {
+    pub code_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Hook messages for credit tokens sent to the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    Deposit {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,31 +66,178 @@ pub enum ExecuteMsg {
         time_ended: u64,
         demanded_tokens: Uint128,
         ipfs_hashes: Vec<String>,
+        methodology: String,
     },
     CreateLendToken {
         lender: Addr,
         amount: Uint128,
+        interest_rate: Option<Decimal>, // annual, left to the lender if not given
+        term: u64, // in seconds
+        proof: Option<Binary>, // eligibility proof, or later with VerifyProof
     },
     
     CastVote {
         claim_id: u64,
         vote: VoteOption,
     },
+    CommitVote {
+        claim_id: u64,
+        commitment: String,
+    },
+    RevealVote {
+        claim_id: u64,
+        vote: VoteOption,
+        salt: String,
+    },
     FinalizeVoting {
         claim_id: u64,
     },
+    DisputeClaim {
+        claim_id: u64,
+    },
     LendTokens {
         lend_request_id: u64,
         response: String,
+        interest_rate: Option<Decimal>, // required if the request did not fix one
+    },
+    CancelLendRequest {
+        lend_request_id: u64,
     },
     RepayTokens {
-        lender: Addr,
+        loan_id: u64,
         amount: Uint128,
     },
-    VerifyEligibility {
-        borrower: Addr,
+    VerifyProof {
+        lend_request_id: u64,
+        proof: Binary,
+        public_inputs: Vec<Binary>, // 32 byte little endian field elements
+    },
+    UpdateVerifyingKey {
+        verifying_key: Binary,
+    },
+    UpdateVotingConfig {
+        voting_period: Option<u64>,
+        min_voters: Option<u64>,
+        quorum: Option<Decimal>,
+        threshold: Option<Decimal>,
+        voting_power: Option<VotingPowerModel>,
+        verifier_min_reputation: Option<Uint128>,
+        verifier_min_bond: Option<Uint128>,
+        commit_reveal: Option<bool>,
+        reveal_period: Option<u64>,
+    },
+    AddVerifier {
+        address: Addr,
+    },
+    RemoveVerifier {
+        address: Addr,
+    },
+    Receive(Cw20ReceiveMsg), // credit tokens sent in with a ReceiveMsg
+    WithdrawCredits {
+        amount: Uint128,
+    },
+    TransferCredits {
+        recipient: Addr,
+        amount: Uint128,
+    },
+    ApproveCredits {
+        spender: Addr,
         amount: Uint128,
-        lender: Addr, // ZK proof
+        expires: Option<Expiration>,
+    },
+    RevokeCredits {
+        spender: Addr,
+    },
+    TransferCreditsFrom {
+        owner: Addr,
+        recipient: Addr,
+        amount: Uint128,
+    },
+    RetireCredits {
+        amount: Uint128,
+        beneficiary: String,
+        reason: String,
+    },
+    PlaceAsk {
+        denom: String,
+        price: Uint128, // in `denom` per credit
+        amount: Uint128,
+    },
+    PlaceBid {
+        price: Uint128, // per credit, paid in the single coin sent as funds
+        amount: Uint128,
+    },
+    CancelOrder {
+        order_id: u64,
+    },
+    BondCredits {
+        amount: Uint128,
+    },
+    UnbondCredits {
+        amount: Uint128,
+    },
+    UpdateVoteIncentives {
+        vote_reward: Option<Uint128>,
+        vote_slash: Option<Uint128>,
+        bond_slash: Option<Decimal>,
+        unrevealed_slash: Option<Uint128>,
+    },
+    UpdateDisputeConfig {
+        challenge_period: Option<u64>,
+        dispute_bond: Option<Uint128>,
+        dispute_min_voters: Option<u64>,
+        dispute_quorum: Option<Decimal>,
+    },
+    UpdateClaimConfig {
+        max_claim_period: u64,
+    },
+    UpdateLoanConfig {
+        max_loan_term: Option<u64>,
+        loan_grace_period: Option<u64>,
+        default_penalty: Option<Uint128>,
+        seize_defaulted_issuance: Option<bool>,
+        min_eligibility_score: Option<Uint128>,
+    },
+    MarkDefault {
+        loan_id: u64,
+    },
+    UpdateCollateralConfig {
+        collateral: Option<CollateralConfig>, // None turns collateral off for new requests
+    },
+    UpdateMarketConfig {
+        quote_denoms: Vec<String>, // resting orders in a removed denom stay in the book
+    },
+    CreatePool {
+        min_eligibility_score: Uint128,
+        base_rate: Decimal,
+        rate_slope: Decimal,
+        term: u64,
+    },
+    DepositToPool {
+        pool_id: u64,
+        amount: Uint128,
+    },
+    WithdrawFromPool {
+        pool_id: u64,
+        shares: Uint128,
+    },
+    BorrowFromPool {
+        pool_id: u64,
+        amount: Uint128,
+        proof: Binary,
+    },
+    CommitEligibility {
+        commitment: Binary, // zero_knowledge_proofs::BorrowerFigures::commitment, the salt stays with the borrower
+    },
+    AttestEligibility {
+        borrower: Addr,
+        commitment: Binary, // the commitment the owner checked the figures behind
+    },
+    SetMethodology {
+        methodology: Methodology,
+    },
+    RemoveMethodology {
+        id: String,
     },
 }
 
@@ -75,6 +267,83 @@ pub enum QueryMsg {
         start_after: Option<u64>,
         limit: Option<u32>,
     },
+    GetVerifier {
+        address: Addr,
+    },
+    GetVerifiers {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    GetVotingRecord {
+        address: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetDispute {
+        claim_id: u64,
+    },
+    GetConflictingClaims {
+        longitudes: Vec<String>,
+        latitudes: Vec<String>,
+        time_started: u64,
+        time_ended: u64,
+    },
+    GetAllowance {
+        owner: Addr,
+        spender: Addr,
+    },
+    GetAllowances {
+        owner: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    GetCreditBatches {
+        organization: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetRetirement {
+        id: u64,
+    },
+    GetOrder {
+        id: u64,
+    },
+    GetPool {
+        pool_id: u64,
+    },
+    GetPools {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetPoolShares {
+        pool_id: u64,
+        address: Addr,
+    },
+    GetOrderBook {
+        denom: String,
+        side: OrderSide,
+        start_after: Option<u64>, // order id
+        limit: Option<u32>,
+    },
+    GetRetirementsByOrganization {
+        organization: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetMethodology {
+        id: String,
+    },
+    GetMethodologies {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    PreviewClaimCap {
+        methodology: String,
+        longitudes: Vec<String>,
+        latitudes: Vec<String>,
+        time_started: u64,
+        time_ended: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -82,6 +351,31 @@ pub struct ConfigResponse {
     pub owner: Addr,
     pub voting_period: u64,
     pub total_carbon_credits: Uint128,
+    pub min_voters: u64,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_power: VotingPowerModel,
+    pub verifier_min_reputation: Uint128,
+    pub verifier_min_bond: Uint128,
+    pub vote_reward: Uint128,
+    pub vote_slash: Uint128,
+    pub bond_slash: Decimal,
+    pub commit_reveal: bool,
+    pub reveal_period: u64,
+    pub unrevealed_slash: Uint128,
+    pub challenge_period: u64,
+    pub dispute_bond: Uint128,
+    pub dispute_min_voters: u64,
+    pub dispute_quorum: Decimal,
+    pub max_claim_period: u64,
+    pub credit_token: Option<Addr>,
+    pub max_loan_term: u64,
+    pub loan_grace_period: u64,
+    pub default_penalty: Uint128,
+    pub seize_defaulted_issuance: bool,
+    pub min_eligibility_score: Uint128,
+    pub collateral: Option<CollateralConfig>,
+    pub quote_denoms: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -94,10 +388,15 @@ pub struct ClaimResponse {
     pub time_ended: u64,
     pub demanded_tokens: Uint128,
     pub ipfs_hashes: Vec<String>,
+    pub methodology: String,
+    pub polygon: Polygon,
     pub status: ClaimStatus,
     pub voting_end_time: u64,
+    pub commit_reveal: bool,
+    pub reveal_end_time: u64,
     pub yes_votes: Uint128,
     pub no_votes: Uint128,
+    pub finalized_at: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -111,6 +410,8 @@ pub struct OrganizationResponse {
     pub total_returned: Uint128,
     pub name: String,
     pub emissions: Uint128,
+    pub interest_owed: Uint128,
+    pub total_interest_paid: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -134,4 +435,116 @@ pub struct OrganizationListItem {
     pub name: String,
     pub reputation_score: Uint128,
     pub carbon_credits: Uint128,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifierResponse {
+    pub address: Addr,
+    pub bonded_credits: Uint128,
+    pub added_at: u64,
+    pub pending_votes: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifiersResponse {
+    pub verifiers: Vec<VerifierResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotingRecordResponse {
+    pub address: Addr,
+    pub records: Vec<VoteRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowanceResponse {
+    pub owner: Addr,
+    pub spender: Addr,
+    pub amount: Uint128,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowancesResponse {
+    pub allowances: Vec<AllowanceResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreditBatchesResponse {
+    pub organization: Addr,
+    pub batches: Vec<CreditBatch>,
+    pub unattributed: Uint128, // balance without a source claim, e.g. a restored bond slash
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RetirementsResponse {
+    pub retirements: Vec<RetirementCertificate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MethodologyResponse {
+    pub id: String,
+    pub land_type: String,
+    pub credits_per_hectare_year: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MethodologiesResponse {
+    pub methodologies: Vec<MethodologyResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimCapResponse {
+    pub methodology: String,
+    pub area: Uint128, // in square meters
+    pub period: u64,   // in seconds
+    pub max_tokens: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConflictingClaimsResponse {
+    pub claim_ids: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeResponse {
+    pub claim_id: u64,
+    pub disputer: Addr,
+    pub bond: Uint128,
+    pub original_status: ClaimStatus,
+    pub opened_at: u64,
+    pub status: DisputeStatus,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OrderBookResponse {
+    pub denom: String,
+    pub side: OrderSide,
+    pub orders: Vec<MarketOrder>, // best price first, then oldest first
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolResponse {
+    pub id: u64,
+    pub min_eligibility_score: Uint128,
+    pub base_rate: Decimal,
+    pub rate_slope: Decimal,
+    pub term: u64,
+    pub liquidity: Uint128,
+    pub borrowed: Uint128,
+    pub total_shares: Uint128,
+    pub utilization: Decimal,
+    pub borrow_rate: Decimal, // for a loan drawn now, before its own effect on utilization
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolsResponse {
+    pub pools: Vec<PoolResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolSharesResponse {
+    pub pool_id: u64,
+    pub address: Addr,
+    pub shares: Uint128,
+    pub value: Uint128, // credits the shares would withdraw
+}
@@ -1,13 +1,56 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::geometry::Polygon;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: Addr,
     pub voting_period: u64, // in seconds
     pub total_carbon_credits: Uint128,
+    pub min_voters: u64,
+    pub quorum: Decimal,   // share of registered verifiers that must vote
+    pub threshold: Decimal, // share of cast votes that must be Yes
+    pub voting_power: VotingPowerModel,
+    pub verifier_min_reputation: Uint128,
+    pub verifier_min_bond: Uint128,
+    pub vote_reward: Uint128,  // reputation gained for voting with the outcome
+    pub vote_slash: Uint128,   // reputation lost for voting against it
+    pub bond_slash: Decimal,   // share of bonded credits burned for a wrong vote
+    pub commit_reveal: bool,
+    pub reveal_period: u64, // in seconds, follows the voting period
+    pub unrevealed_slash: Uint128, // reputation lost for a commit that is never revealed
+    pub challenge_period: u64, // in seconds after finalization
+    pub dispute_bond: Uint128,
+    pub dispute_min_voters: u64,
+    pub dispute_quorum: Decimal,
+    pub max_claim_period: u64, // in seconds
+    pub credit_token: Option<Addr>, // CW20 minted for approved claims, if any
+    pub max_loan_term: u64, // in seconds, bounds a loan's maturity
+    pub loan_grace_period: u64, // in seconds after maturity before anyone may mark a default
+    pub default_penalty: Uint128, // reputation lost by a defaulting borrower
+    pub seize_defaulted_issuance: bool, // redirect a defaulter's new credits to its lenders
+    pub min_eligibility_score: Uint128, // score borrowers must prove to request a loan
+    pub collateral: Option<CollateralConfig>, // native collateral required with lend requests, if any
+    pub quote_denoms: Vec<String>, // native denoms orders may be priced in
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollateralConfig {
+    pub denom: String,
+    pub ratio: Decimal, // collateral required per borrowed credit
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingPowerModel {
+    OneAddressOneVote,
+    Reputation,   // weight = reputation_score
+    Credits,      // weight = credits bonded as a verifier
+    Quadratic,    // weight = sqrt(bonded credits)
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -20,14 +63,63 @@ pub enum VoteOption {
 pub struct Vote {
     pub voter: Addr,
     pub vote: VoteOption,
+    pub weight: Uint128,
     pub timestamp: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteCommitment {
+    pub commitment: String, // hex sha256 of "{voter}:{claim_id}:{yes|no}:{salt}"
+    pub weight: Uint128,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteRecord {
+    pub claim_id: u64,
+    pub vote: VoteOption,
+    pub weight: Uint128,
+    pub correct: bool,
+    pub reputation_gained: Uint128,
+    pub reputation_lost: Uint128,
+    pub bond_slashed: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifierInfo {
+    pub bonded_credits: Uint128,
+    pub added_at: u64,
+    pub pending_votes: u64, // votes and commits on claims not yet finalized; the bond is locked while nonzero
+    pub bond_batches: Vec<CreditBatch>, // bonded credits by source claim, oldest first
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum ClaimStatus {
     Active,
     Approved,
     Rejected,
+    FailedQuorum,
+    Disputed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum DisputeStatus {
+    Open,
+    Upheld,      // original decision stands, bond is burned
+    Overturned,  // original decision reversed, bond is returned
+    FailedQuorum, // original decision stands, bond is returned
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Dispute {
+    pub claim_id: u64,
+    pub disputer: Addr,
+    pub bond: Uint128,
+    pub original_status: ClaimStatus,
+    pub opened_at: u64,
+    pub status: DisputeStatus,
+    pub bond_batches: Vec<CreditBatch>, // vintages of the escrowed bond, handed back on a refund
+    pub original_voters: Vec<Addr>,     // first-round voters re-judged if the decision is overturned
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -35,6 +127,16 @@ pub enum LentStatus {
     Active, 
     Approved,
     Rejected,
+    Repaid,
+    Defaulted,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Methodology {
+    pub id: String,
+    pub land_type: String,
+    pub credits_per_hectare_year: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -47,10 +149,69 @@ pub struct Claim {
     pub time_ended: u64,
     pub demanded_tokens: Uint128,
     pub ipfs_hashes: Vec<String>,
+    pub methodology: String,
+    pub polygon: Polygon,
     pub status: ClaimStatus,
     pub voting_end_time: u64,
+    pub commit_reveal: bool,
+    pub reveal_end_time: u64,
     pub yes_votes: Uint128,
     pub no_votes: Uint128,
+    pub finalized_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Allowance {
+    pub amount: Uint128,
+    pub expires: Expiration,
+}
+
+/// Credits issued by one approved claim, held by one organization.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreditBatch {
+    pub claim_id: u64,
+    pub vintage: u32, // calendar year the claimed period ended in
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RetirementCertificate {
+    pub id: u64,
+    pub organization: Addr,
+    pub beneficiary: String,
+    pub amount: Uint128,
+    pub reason: String,
+    pub block_height: u64,
+    pub timestamp: u64,
+    pub batches: Vec<CreditBatch>, // retired credits by source claim, unattributed credits are not listed
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum OrderSide {
+    Ask,
+    Bid,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum OrderStatus {
+    Open,
+    Filled,
+    Cancelled,
+}
+
+/// A limit order to sell (ask) or buy (bid) credits for a native denom.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketOrder {
+    pub id: u64,
+    pub owner: Addr,
+    pub side: OrderSide,
+    pub denom: String,
+    pub price: Uint128, // in `denom` per credit
+    pub amount: Uint128,
+    pub remaining: Uint128,
+    pub batches: Vec<CreditBatch>, // escrowed batches of an ask, oldest first
+    pub status: OrderStatus,
+    pub created_at: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -63,7 +224,37 @@ pub struct LendRequest {
     pub proof_data: String, 
     pub status: LentStatus,
     pub time: u64,
+    pub interest_rate: Option<Decimal>, // simple annual rate, set by the borrower or by the lender on acceptance
+    pub term: u64, // in seconds
+    pub maturity: Option<u64>,
+    pub outstanding_principal: Uint128,
+    pub accrued_interest: Uint128,
+    pub accrued_at: u64,
+    pub defaulted_at: Option<u64>,
+    pub collateral: Option<Coin>, // held in escrow until repaid, denied or defaulted
+    pub pool_id: Option<u64>, // set for loans drawn from a pool, the lender is then this contract
+}
 
+/// A borrower's commitment to its eligibility figures, usable in a proof once the owner
+/// has checked the figures behind it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EligibilityCommitment {
+    pub commitment: Binary,
+    pub attested: bool,
+}
+
+/// Credits deposited by lenders for borrowers to draw from, tracked in shares.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pool {
+    pub id: u64,
+    pub min_eligibility_score: Uint128,
+    pub base_rate: Decimal, // annual rate at zero utilization
+    pub rate_slope: Decimal, // added to the rate at full utilization
+    pub term: u64, // in seconds
+    pub liquidity: Uint128, // credits available to borrow or withdraw
+    pub borrowed: Uint128, // principal lent out and not written off
+    pub total_shares: Uint128,
+    pub batches: Vec<CreditBatch>, // liquidity by source claim, oldest first
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -76,6 +267,8 @@ pub struct OrganizationInfo {
     pub total_returned: Uint128,
     pub name: String,
     pub emissions: Uint128,
+    pub interest_owed: Uint128, // accrued as of the last repayment
+    pub total_interest_paid: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -89,6 +282,13 @@ pub struct LendRequestResponse {
     pub time: u64,
     pub amount: Uint128,
     pub role: String,  // "borrower" or "lender"
+    pub interest_rate: Option<Decimal>,
+    pub term: u64,
+    pub maturity: Option<u64>,
+    pub outstanding_principal: Uint128,
+    pub accrued_interest: Uint128, // up to the queried block
+    pub defaulted_at: Option<u64>,
+    pub collateral: Option<Coin>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -99,7 +299,37 @@ pub struct UserLendRequestsResponse {
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const CLAIMS: Map<u64, Claim> = Map::new("claims");
 pub const LEND_REQUESTS : Map<u64, LendRequest> = Map::new("lend_requests");
-pub const VOTES: Map<(u64, &Addr), VoteOption> = Map::new("votes");
+pub const VOTES: Map<(u64, &Addr), Vote> = Map::new("votes");
+pub const COMMITMENTS: Map<(u64, &Addr), VoteCommitment> = Map::new("commitments");
+pub const DISPUTES: Map<u64, Dispute> = Map::new("disputes");
+// Spatial index of active, disputed and approved claims keyed by (tile_x, tile_y, claim_id)
+pub const CLAIM_TILES: Map<(i32, i32, u64), Empty> = Map::new("claim_tiles");
+pub const METHODOLOGIES: Map<&str, Methodology> = Map::new("methodologies");
+// Provenance of each organization's balance keyed by (holder, source claim id), consumed FIFO
+pub const CREDIT_BATCHES: Map<(&Addr, u64), CreditBatch> = Map::new("credit_batches");
+// Credits a spender may move on an owner's behalf keyed by (owner, spender)
+pub const ALLOWANCES: Map<(&Addr, &Addr), Allowance> = Map::new("allowances");
+pub const RETIREMENTS: Map<u64, RetirementCertificate> = Map::new("retirements");
+pub const ORGANIZATION_RETIREMENTS: Map<(&Addr, u64), Empty> = Map::new("organization_retirements");
+pub const RETIREMENT_COUNTER: Item<u64> = Item::new("retirement_counter");
+pub const MARKET_ORDERS: Map<u64, MarketOrder> = Map::new("market_orders");
+// Open orders by (denom, price, id); bids are keyed by u128::MAX - price so the best bid sorts first
+pub const ASKS: Map<(&str, u128, u64), Empty> = Map::new("asks");
+pub const BIDS: Map<(&str, u128, u64), Empty> = Map::new("bids");
+pub const ORDER_COUNTER: Item<u64> = Item::new("order_counter");
 pub const CLAIM_COUNTER: Item<u64> = Item::new("claim_counter");
 pub const ORGANIZATIONS: Map<&Addr, OrganizationInfo> = Map::new("organizations");
-pub const LEND_REQUEST_COUNTER: Item<u64> = Item::new("lend_request_counter");
\ No newline at end of file
+pub const LEND_REQUEST_COUNTER: Item<u64> = Item::new("lend_request_counter");
+// Defaulted loans that are not yet cleared keyed by (borrower, loan id)
+pub const DEFAULTED_LOANS: Map<(&Addr, u64), Empty> = Map::new("defaulted_loans");
+pub const POOLS: Map<u64, Pool> = Map::new("pools");
+pub const POOL_SHARES: Map<(u64, &Addr), Uint128> = Map::new("pool_shares");
+pub const POOL_COUNTER: Item<u64> = Item::new("pool_counter");
+// Groth16 verifying key of the eligibility circuit, set by the owner
+pub const VERIFYING_KEY: Item<Binary> = Item::new("verifying_key");
+// Eligibility commitment each borrower last posted
+pub const ELIGIBILITY_COMMITMENTS: Map<&Addr, EligibilityCommitment> = Map::new("eligibility_commitments");
+pub const VERIFIERS: Map<&Addr, VerifierInfo> = Map::new("verifiers");
+// Number of entries in VERIFIERS, the electorate turnout is measured against
+pub const VERIFIER_COUNT: Item<u64> = Item::new("verifier_count");
+pub const VOTE_RECORDS: Map<(&Addr, u64), VoteRecord> = Map::new("vote_records");
\ No newline at end of file
@@ -0,0 +1,168 @@
+//! A small R1CS builder over the BN254 scalar field.
+//!
+//! Every [`Wire`] carries its linear combination together with its value, so a
+//! circuit is written once as straight-line code and serves both the setup,
+//! where the values are placeholders, and proving. Additions and scaling are
+//! free; each multiplication adds one constraint.
+//!
+//! On top of the gates sit comparison gadgets for values of a known bit width:
+//! [`CircuitBuilder::to_bits`], [`CircuitBuilder::range_check`],
+//! [`CircuitBuilder::less_than`], [`CircuitBuilder::min`] and
+//! [`CircuitBuilder::max`]. A `bits` wide decomposition costs `bits + 1`
+//! constraints.
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSystemRef, LinearCombination, SynthesisError, Variable},
+};
+use ark_std::vec::Vec;
+
+/// Widest decomposition the gadgets accept, leaving headroom below the 254 bit modulus.
+pub const MAX_BITS: usize = 250;
+
+/// A linear combination of circuit variables and the value it takes.
+#[derive(Clone, Debug)]
+pub struct Wire {
+    combination: LinearCombination<Fr>,
+    value: Fr,
+}
+
+impl Wire {
+    /// The value the wire carries in this synthesis; a placeholder during setup.
+    pub fn value(&self) -> Fr {
+        self.value
+    }
+}
+
+/// Allocates wires and enforces gates on an arkworks constraint system.
+pub struct CircuitBuilder {
+    cs: ConstraintSystemRef<Fr>,
+}
+
+impl CircuitBuilder {
+    /// Wraps the constraint system handed to `ConstraintSynthesizer::generate_constraints`.
+    pub fn new(cs: ConstraintSystemRef<Fr>) -> Self {
+        CircuitBuilder { cs }
+    }
+
+    /// Allocates a public input, in the order the verifier passes them.
+    pub fn public_input(&mut self, value: Fr) -> Result<Wire, SynthesisError> {
+        let variable = self.cs.new_input_variable(|| Ok(value))?;
+        Ok(Wire { combination: lc!() + variable, value })
+    }
+
+    /// Allocates a private witness known only to the prover.
+    pub fn witness(&mut self, value: Fr) -> Result<Wire, SynthesisError> {
+        let variable = self.cs.new_witness_variable(|| Ok(value))?;
+        Ok(Wire { combination: lc!() + variable, value })
+    }
+
+    /// A wire fixed to `value`, costing no variable.
+    pub fn constant(&self, value: Fr) -> Wire {
+        Wire { combination: lc!() + (value, Variable::One), value }
+    }
+
+    /// `a + b`, without a constraint.
+    pub fn add(&self, a: &Wire, b: &Wire) -> Wire {
+        Wire { combination: a.combination.clone() + &b.combination, value: a.value + b.value }
+    }
+
+    /// `a - b`, without a constraint.
+    pub fn sub(&self, a: &Wire, b: &Wire) -> Wire {
+        Wire { combination: a.combination.clone() - &b.combination, value: a.value - b.value }
+    }
+
+    /// `factor * a`, without a constraint.
+    pub fn scale(&self, a: &Wire, factor: Fr) -> Wire {
+        Wire { combination: a.combination.clone() * factor, value: a.value * factor }
+    }
+
+    /// Sums the wires, each scaled by its coefficient.
+    pub fn linear_combination(&self, terms: &[(Fr, &Wire)]) -> Wire {
+        terms.iter().fold(self.constant(Fr::from(0u8)), |sum, (factor, wire)| {
+            self.add(&sum, &self.scale(wire, *factor))
+        })
+    }
+
+    /// Allocates the product as a new witness and constrains it to `a * b`.
+    pub fn mul(&mut self, a: &Wire, b: &Wire) -> Result<Wire, SynthesisError> {
+        let product = self.witness(a.value * b.value)?;
+        self.cs.enforce_constraint(a.combination.clone(), b.combination.clone(), product.combination.clone())?;
+        Ok(product)
+    }
+
+    /// Pins a linear combination to a single variable, so later gates reuse it cheaply.
+    pub fn collapse(&mut self, a: &Wire) -> Result<Wire, SynthesisError> {
+        let collapsed = self.witness(a.value)?;
+        self.assert_equal(a, &collapsed)?;
+        Ok(collapsed)
+    }
+
+    /// Constrains `a` and `b` to carry the same value.
+    pub fn assert_equal(&mut self, a: &Wire, b: &Wire) -> Result<(), SynthesisError> {
+        self.cs.enforce_constraint(a.combination.clone() - &b.combination, lc!() + Variable::One, lc!())
+    }
+
+    /// Constrains `a` to be 0 or 1.
+    pub fn assert_boolean(&mut self, a: &Wire) -> Result<(), SynthesisError> {
+        let one = self.constant(Fr::from(1u8));
+        let complement = self.sub(a, &one);
+        self.cs.enforce_constraint(a.combination.clone(), complement.combination, lc!())
+    }
+
+    /// Decomposes `a` into `bits` little endian boolean wires, which constrains it to `[0, 2^bits)`.
+    ///
+    /// # Panics
+    ///
+    /// If `bits` exceeds [`MAX_BITS`].
+    pub fn to_bits(&mut self, a: &Wire, bits: usize) -> Result<Vec<Wire>, SynthesisError> {
+        assert!(bits <= MAX_BITS, "cannot decompose into {} bits", bits);
+        let value_bits = a.value.into_bigint().to_bits_le();
+        let mut decomposition = Vec::with_capacity(bits);
+        let mut sum = self.constant(Fr::from(0u8));
+        let mut coefficient = Fr::from(1u8);
+        // Bits past the width are dropped, leaving an unsatisfiable circuit for out of range values
+        for bit in value_bits.into_iter().chain(core::iter::repeat(false)).take(bits) {
+            let bit = self.witness(Fr::from(bit))?;
+            self.assert_boolean(&bit)?;
+            sum = self.add(&sum, &self.scale(&bit, coefficient));
+            coefficient.double_in_place();
+            decomposition.push(bit);
+        }
+        self.assert_equal(&sum, a)?;
+        Ok(decomposition)
+    }
+
+    /// Constrains `a` to `[0, 2^bits)`.
+    pub fn range_check(&mut self, a: &Wire, bits: usize) -> Result<(), SynthesisError> {
+        self.to_bits(a, bits).map(|_| ())
+    }
+
+    /// A boolean wire, 1 when `a < b`.
+    ///
+    /// `a - b` must lie in `(-2^bits, 2^bits)`, which holds for any two values that
+    /// are range checked to `bits` and for a signed difference of that size alike.
+    pub fn less_than(&mut self, a: &Wire, b: &Wire, bits: usize) -> Result<Wire, SynthesisError> {
+        // a - b + 2^bits has its top bit set exactly when a >= b
+        let offset = self.constant(Fr::from(2u8).pow([bits as u64]));
+        let shifted = self.add(&self.sub(a, b), &offset);
+        let decomposition = self.to_bits(&shifted, bits + 1)?;
+        Ok(self.sub(&self.constant(Fr::from(1u8)), &decomposition[bits]))
+    }
+
+    /// The smaller of `a` and `b`, under the same bounds as [`CircuitBuilder::less_than`].
+    pub fn min(&mut self, a: &Wire, b: &Wire, bits: usize) -> Result<Wire, SynthesisError> {
+        let a_smaller = self.less_than(a, b, bits)?;
+        // b + (a < b) * (a - b)
+        let correction = self.mul(&a_smaller, &self.sub(a, b))?;
+        Ok(self.add(b, &correction))
+    }
+
+    /// The larger of `a` and `b`, under the same bounds as [`CircuitBuilder::less_than`].
+    pub fn max(&mut self, a: &Wire, b: &Wire, bits: usize) -> Result<Wire, SynthesisError> {
+        let min = self.min(a, b, bits)?;
+        Ok(self.sub(&self.add(a, b), &min))
+    }
+}
@@ -0,0 +1,38 @@
+use ark_bn254::Fr;
+use ark_ff::{Field, PrimeField, Zero};
+use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
+
+// x^5 is a permutation of the BN254 scalar field, 110 rounds cover its 254 bits
+pub(crate) const MIMC_ROUNDS: usize = 110;
+
+pub(crate) fn round_constants() -> Vec<Fr> {
+    (0..MIMC_ROUNDS as u32)
+        .map(|round| {
+            if round == 0 {
+                return Fr::zero();
+            }
+            let digest = Sha256::new()
+                .chain_update(b"carbon-credit-mimc")
+                .chain_update(round.to_le_bytes())
+                .finalize();
+            Fr::from_le_bytes_mod_order(&digest)
+        })
+        .collect()
+}
+
+fn mimc_encrypt(constants: &[Fr], message: Fr, key: Fr) -> Fr {
+    let mut state = message;
+    for constant in constants {
+        state = (state + key + constant).pow([5u64]);
+    }
+    state + key
+}
+
+/// MiMC in Miyaguchi-Preneel mode, the hash the eligibility circuit recomputes.
+pub(crate) fn mimc_hash(values: &[Fr]) -> Fr {
+    let constants = round_constants();
+    values.iter().fold(Fr::zero(), |hash, value| {
+        mimc_encrypt(&constants, *value, hash) + hash + value
+    })
+}
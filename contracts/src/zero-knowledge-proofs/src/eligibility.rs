@@ -0,0 +1,189 @@
+use ark_bn254::Fr;
+use ark_ff::PrimeField;
+use ark_std::{vec, vec::Vec};
+
+use crate::commitment::mimc_hash;
+use crate::field;
+
+/// A borrower's figures, committed to on-chain and witnessed by the proof.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BorrowerFigures {
+    /// Net emissions
+    pub emissions: u64,
+    /// Credits returned on past loans
+    pub returned: u64,
+    /// Credits borrowed over all loans
+    pub borrowed: u64,
+    /// Outstanding debt
+    pub debt: u64,
+    /// Carbon credits held
+    pub credits: u64,
+    /// Reputation score
+    pub reputation: u64,
+}
+
+impl BorrowerFigures {
+    fn field_elements(&self, salt: &[u8; 32]) -> [Fr; 7] {
+        [
+            Fr::from(self.emissions),
+            Fr::from(self.returned),
+            Fr::from(self.borrowed),
+            Fr::from(self.debt),
+            Fr::from(self.credits),
+            Fr::from(self.reputation),
+            Fr::from_le_bytes_mod_order(salt),
+        ]
+    }
+
+    /// Hash commitment to the figures, blinded by `salt`, as the borrower posts it on-chain.
+    pub fn commitment(&self, salt: &[u8; 32]) -> Vec<u8> {
+        field::encode(&self.commitment_field(salt))
+    }
+
+    pub(crate) fn commitment_field(&self, salt: &[u8; 32]) -> Fr {
+        mimc_hash(&self.field_elements(salt))
+    }
+}
+
+/// Everything the prover knows: the private figures and the public terms of the loan.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EligibilityInputs {
+    /// The committed figures, private
+    pub borrower: BorrowerFigures,
+    /// Blinding of the commitment, private
+    pub salt: [u8; 32],
+    /// Carbon credits the lender holds, public
+    pub lender_credits: u64,
+    /// Debt the lender carries, public
+    pub lender_debt: u64,
+    /// Score the proof shows the figures reach, public
+    pub threshold: u64,
+}
+
+impl EligibilityInputs {
+    /// min(reputation, 100) * (credits + lender credits) - (debt + lender debt)
+    /// - 2 * emissions + 100 * returned - 50 * borrowed
+    pub fn score(&self) -> i128 {
+        eligibility_score(&self.borrower, self.lender_credits, self.lender_debt)
+    }
+
+    /// Encoded public inputs of the statement these inputs prove.
+    pub fn public_inputs(&self) -> Vec<Vec<u8>> {
+        self.statement().iter().map(field::encode).collect()
+    }
+
+    // Commitment, lender credits, lender debt and threshold
+    pub(crate) fn statement(&self) -> Vec<Fr> {
+        statement(self.borrower.commitment_field(&self.salt), self.lender_credits, self.lender_debt, self.threshold)
+    }
+}
+
+/// Reputation beyond this counts no further towards the score.
+pub const REPUTATION_CAP: u64 = 100;
+
+/// The score [`EligibilityInputs::score`] computes, for figures known in the clear.
+pub fn eligibility_score(borrower: &BorrowerFigures, lender_credits: u64, lender_debt: u64) -> i128 {
+    let reputation = borrower.reputation.min(REPUTATION_CAP);
+    let term_a = reputation as i128 * (borrower.credits as i128 + lender_credits as i128);
+    let term_b = borrower.debt as i128 + lender_debt as i128;
+    let term_c = 2 * borrower.emissions as i128;
+    let term_d = 100 * borrower.returned as i128 - 50 * borrower.borrowed as i128;
+    term_a - term_b - term_c + term_d
+}
+
+// Commitment, lender credits, lender debt and threshold
+pub(crate) const STATEMENT_LENGTH: usize = 4;
+
+pub(crate) fn statement(commitment: Fr, lender_credits: u64, lender_debt: u64, threshold: u64) -> Vec<Fr> {
+    vec![commitment, Fr::from(lender_credits), Fr::from(lender_debt), Fr::from(threshold)]
+}
+
+#[cfg(feature = "prover")]
+pub(crate) use self::circuit::EligibilityCircuit;
+
+#[cfg(feature = "prover")]
+mod circuit {
+    use ark_bn254::Fr;
+    use ark_ff::Zero;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use ark_std::vec::Vec;
+
+    use super::{EligibilityInputs, REPUTATION_CAP, STATEMENT_LENGTH};
+    use crate::circuit::{CircuitBuilder, Wire};
+    use crate::commitment::round_constants;
+
+    // Wide enough for the difference of any score of u64 figures and a u64 threshold
+    const SCORE_BITS: usize = 128;
+    const FIGURE_BITS: usize = 64;
+
+    pub(crate) struct EligibilityCircuit {
+        pub inputs: EligibilityInputs,
+    }
+
+    impl ConstraintSynthesizer<Fr> for EligibilityCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let mut builder = CircuitBuilder::new(cs);
+            let mut statement = Vec::with_capacity(STATEMENT_LENGTH);
+            for value in self.inputs.statement() {
+                statement.push(builder.public_input(value)?);
+            }
+            let (commitment, lender_credits, lender_debt, threshold) =
+                (&statement[0], &statement[1], &statement[2], &statement[3]);
+
+            let mut private = Vec::with_capacity(7);
+            for value in self.inputs.borrower.field_elements(&self.inputs.salt).iter() {
+                private.push(builder.witness(*value)?);
+            }
+            let (emissions, returned, borrowed, debt, credits, reputation) =
+                (&private[0], &private[1], &private[2], &private[3], &private[4], &private[5]);
+
+            // Figures outside u64 could stand in for negative debt or emissions
+            for figure in private.iter().take(6) {
+                builder.range_check(figure, FIGURE_BITS)?;
+            }
+
+            // The figures are the ones the borrower committed to
+            let hash = mimc_hash(&mut builder, &private)?;
+            builder.assert_equal(&hash, commitment)?;
+
+            let cap = builder.constant(Fr::from(REPUTATION_CAP));
+            let reputation = builder.min(reputation, &cap, FIGURE_BITS)?;
+            let total_credits = builder.add(credits, lender_credits);
+            let term_a = builder.mul(&reputation, &total_credits)?;
+            let one = Fr::from(1u8);
+            let score = builder.linear_combination(&[
+                (one, &term_a),
+                (-one, debt),
+                (-one, lender_debt),
+                (-Fr::from(2u8), emissions),
+                (Fr::from(100u8), returned),
+                (-Fr::from(50u8), borrowed),
+            ]);
+
+            // A negative score compares as such, so it stays below any threshold
+            let below = builder.less_than(&score, threshold, SCORE_BITS)?;
+            builder.assert_equal(&below, &builder.constant(Fr::zero()))
+        }
+    }
+
+    // Recomputes commitment::mimc_hash over the wires
+    fn mimc_hash(builder: &mut CircuitBuilder, messages: &[Wire]) -> Result<Wire, SynthesisError> {
+        let constants = round_constants();
+        let one = Fr::from(1u8);
+        let mut hash = builder.constant(Fr::zero());
+        for message in messages {
+            let mut state = message.clone();
+            for constant in constants.iter() {
+                // (state + hash + constant)^5 as square, fourth and fifth power
+                let base = builder.linear_combination(&[(one, &state), (one, &hash), (*constant, &builder.constant(one))]);
+                let square = builder.mul(&base, &base)?;
+                let fourth = builder.mul(&square, &square)?;
+                state = builder.mul(&fourth, &base)?;
+            }
+            // Miyaguchi-Preneel: E_h(m) + h + m, where E_h(m) ends by adding the key once more
+            let next = builder.linear_combination(&[(one, &state), (Fr::from(2u8), &hash), (one, message)]);
+            hash = builder.collapse(&next)?;
+        }
+        Ok(hash)
+    }
+}
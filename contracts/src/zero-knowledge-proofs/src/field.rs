@@ -0,0 +1,50 @@
+//! BN254 scalar field elements and their byte encoding.
+//!
+//! Commitments and public inputs cross the contract boundary as 32 byte little
+//! endian encodings. Only canonical encodings, below the field modulus, decode,
+//! so every element has exactly one byte representation.
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+pub use ark_bn254::Fr;
+
+use crate::ProofError;
+
+/// Length of an encoded field element.
+pub const ENCODED_LENGTH: usize = 32;
+
+/// Encodes `value` as 32 little endian bytes.
+pub fn encode(value: &Fr) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(ENCODED_LENGTH);
+    value.serialize_compressed(&mut bytes).expect("writing to a vec cannot fail");
+    bytes
+}
+
+/// Decodes a canonical 32 byte encoding.
+pub fn decode(bytes: &[u8]) -> Result<Fr, ProofError> {
+    if bytes.len() != ENCODED_LENGTH {
+        return Err(ProofError::Serialization);
+    }
+    Fr::deserialize_compressed(bytes).map_err(|_| ProofError::Serialization)
+}
+
+/// Decodes an element below 2^64, as thresholds and figures are.
+pub fn decode_u64(bytes: &[u8]) -> Result<u64, ProofError> {
+    let limbs = decode(bytes)?.into_bigint().0;
+    if limbs[1..].iter().any(|limb| *limb != 0) {
+        return Err(ProofError::Serialization);
+    }
+    Ok(limbs[0])
+}
+
+/// Maps a signed integer into the field, negative values wrapping around the modulus.
+pub fn from_signed(value: i128) -> Fr {
+    let magnitude = Fr::from(value.unsigned_abs());
+    if value < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
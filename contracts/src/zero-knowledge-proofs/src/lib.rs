@@ -0,0 +1,201 @@
+//! Groth16 eligibility proofs over BN254.
+//!
+//! A borrower commits to its figures on-chain with
+//! [`BorrowerFigures::commitment`] and proves off-chain that the figures behind
+//! the commitment score at least a threshold. The contract checks the proof
+//! against a verifying key it stores. The commitment only binds the proof to
+//! figures someone has vouched for; it keeps them from no one the borrower
+//! shows them to, and most of them are recorded on-chain anyway.
+//!
+//! Keys and proofs are plain byte vectors. Without the default `std` and
+//! `prover` features the crate is a `no_std` verifier, which is all the
+//! contract links.
+//!
+//! A borrower's side of a loan looks like this:
+//!
+//! ```
+//! # #[cfg(feature = "prover")]
+//! # fn main() {
+//! use ark_std::rand::{rngs::StdRng, SeedableRng};
+//! use zero_knowledge_proofs::{
+//!     eligibility_proof, eligibility_public_inputs, generate_keys, verify_proof, BorrowerFigures,
+//!     EligibilityInputs,
+//! };
+//!
+//! let mut rng = StdRng::seed_from_u64(0);
+//! let (proving_key, verifying_key) = generate_keys(&mut rng).unwrap();
+//!
+//! let borrower = BorrowerFigures { credits: 10, reputation: 2, ..BorrowerFigures::default() };
+//! let salt = [7; 32];
+//! let commitment = borrower.commitment(&salt);
+//! let inputs = EligibilityInputs { borrower, salt, lender_credits: 1000, lender_debt: 0, threshold: 2000 };
+//! let (score, proof) = eligibility_proof(&proving_key, &inputs, &mut rng).unwrap();
+//! assert_eq!(score, 2020);
+//!
+//! // The verifier only knows the commitment and the terms of the loan
+//! let public_inputs = eligibility_public_inputs(&commitment, 1000, 0, 2000).unwrap();
+//! assert_eq!(verify_proof(&verifying_key, &proof, &public_inputs), Ok(true));
+//! # }
+//! # #[cfg(not(feature = "prover"))]
+//! # fn main() {}
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+#[cfg(feature = "prover")]
+pub mod circuit;
+mod commitment;
+mod eligibility;
+pub mod field;
+#[cfg(all(test, feature = "prover"))]
+mod tests;
+
+use core::fmt;
+
+use ark_bn254::Bn254;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::CanonicalDeserialize;
+use ark_snark::SNARK;
+use ark_std::vec::Vec;
+#[cfg(feature = "prover")]
+use ark_groth16::ProvingKey;
+#[cfg(feature = "prover")]
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "prover")]
+use ark_std::rand::{CryptoRng, RngCore};
+
+#[cfg(feature = "prover")]
+use crate::eligibility::EligibilityCircuit;
+
+use crate::eligibility::STATEMENT_LENGTH;
+pub use crate::eligibility::{eligibility_score, BorrowerFigures, EligibilityInputs, REPUTATION_CAP};
+
+/// Everything that can go wrong generating, proving or verifying.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// Key, proof or public input bytes are not a valid encoding
+    Serialization,
+    /// The number of public inputs does not match the verifying key
+    PublicInputs {
+        /// Inputs the verifying key was generated for
+        expected: usize,
+        /// Inputs supplied
+        actual: usize,
+    },
+    /// The verifying key was not generated for the eligibility circuit
+    VerifyingKey,
+    /// The score is below the threshold, so there is nothing to prove
+    ThresholdNotMet,
+    /// The constraint system rejected the circuit
+    Synthesis(SynthesisError),
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::Serialization => write!(f, "invalid encoding"),
+            ProofError::PublicInputs { expected, actual } => {
+                write!(f, "expected {} public inputs, got {}", expected, actual)
+            }
+            ProofError::VerifyingKey => write!(f, "verifying key does not match the eligibility circuit"),
+            ProofError::ThresholdNotMet => write!(f, "score is below the threshold"),
+            ProofError::Synthesis(err) => write!(f, "constraint synthesis failed: {}", err),
+        }
+    }
+}
+
+impl From<SynthesisError> for ProofError {
+    fn from(err: SynthesisError) -> Self {
+        ProofError::Synthesis(err)
+    }
+}
+
+/// Runs the circuit specific setup, returning the serialized proving and verifying keys.
+#[cfg(feature = "prover")]
+pub fn generate_keys<R: RngCore + CryptoRng>(rng: &mut R) -> Result<(Vec<u8>, Vec<u8>), ProofError> {
+    let circuit = EligibilityCircuit { inputs: EligibilityInputs::default() };
+    let (proving_key, verifying_key) = Groth16::<Bn254>::circuit_specific_setup(circuit, rng)?;
+    // The proving key stays with the prover, uncompressed so it loads without decompressing every point
+    let mut proving_key_bytes = Vec::with_capacity(proving_key.uncompressed_size());
+    proving_key
+        .serialize_uncompressed(&mut proving_key_bytes)
+        .map_err(|_| ProofError::Serialization)?;
+    Ok((proving_key_bytes, serialize(&verifying_key)?))
+}
+
+/// Proves that the committed figures in `inputs` score at least the threshold,
+/// returning the score, which stays with the prover, and the serialized proof.
+#[cfg(feature = "prover")]
+pub fn eligibility_proof<R: RngCore + CryptoRng>(
+    proving_key: &[u8],
+    inputs: &EligibilityInputs,
+    rng: &mut R,
+) -> Result<(i128, Vec<u8>), ProofError> {
+    let score = inputs.score();
+    if score < inputs.threshold as i128 {
+        return Err(ProofError::ThresholdNotMet);
+    }
+    // Trusted like any local secret, so the subgroup checks are skipped
+    let proving_key = ProvingKey::<Bn254>::deserialize_uncompressed_unchecked(proving_key)
+        .map_err(|_| ProofError::Serialization)?;
+    let proof = Groth16::<Bn254>::prove(&proving_key, EligibilityCircuit { inputs: *inputs }, rng)?;
+    Ok((score, serialize(&proof)?))
+}
+
+/// Encoded public inputs for a commitment, the lender's figures and a threshold, as a verifier
+/// that never sees the borrower's figures builds them.
+pub fn eligibility_public_inputs(
+    commitment: &[u8],
+    lender_credits: u64,
+    lender_debt: u64,
+    threshold: u64,
+) -> Result<Vec<Vec<u8>>, ProofError> {
+    let commitment = field::decode(commitment)?;
+    Ok(eligibility::statement(commitment, lender_credits, lender_debt, threshold)
+        .iter()
+        .map(field::encode)
+        .collect())
+}
+
+/// Whether `bytes` is a canonical encoding of a field element, as commitments must be.
+pub fn is_field_element(bytes: &[u8]) -> bool {
+    field::decode(bytes).is_ok()
+}
+
+/// Checks that `verifying_key` decodes and takes the eligibility circuit's public inputs, as a
+/// verifier should before storing it.
+pub fn check_verifying_key(verifying_key: &[u8]) -> Result<(), ProofError> {
+    decode_verifying_key(verifying_key).map(|_| ())
+}
+
+/// Checks a serialized proof against a serialized verifying key and encoded public inputs.
+pub fn verify_proof(verifying_key: &[u8], proof: &[u8], public_inputs: &[Vec<u8>]) -> Result<bool, ProofError> {
+    let verifying_key = decode_verifying_key(verifying_key)?;
+    let proof = Proof::<Bn254>::deserialize_compressed(proof).map_err(|_| ProofError::Serialization)?;
+    if public_inputs.len() != STATEMENT_LENGTH {
+        return Err(ProofError::PublicInputs { expected: STATEMENT_LENGTH, actual: public_inputs.len() });
+    }
+    let public_inputs = public_inputs
+        .iter()
+        .map(|input| field::decode(input))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof)?)
+}
+
+// One base point per public input plus the constant term
+fn decode_verifying_key(bytes: &[u8]) -> Result<VerifyingKey<Bn254>, ProofError> {
+    let verifying_key = VerifyingKey::<Bn254>::deserialize_compressed(bytes).map_err(|_| ProofError::Serialization)?;
+    if verifying_key.gamma_abc_g1.len() != STATEMENT_LENGTH + 1 {
+        return Err(ProofError::VerifyingKey);
+    }
+    Ok(verifying_key)
+}
+
+#[cfg(feature = "prover")]
+fn serialize<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>, ProofError> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value.serialize_compressed(&mut bytes).map_err(|_| ProofError::Serialization)?;
+    Ok(bytes)
+}
@@ -0,0 +1,238 @@
+use std::sync::OnceLock;
+
+use ark_bn254::Bn254;
+use ark_groth16::VerifyingKey;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+use crate::circuit::{CircuitBuilder, Wire, MAX_BITS};
+use crate::eligibility::EligibilityCircuit;
+use crate::field::{self, Fr};
+use crate::{
+    check_verifying_key, eligibility_proof, eligibility_public_inputs, eligibility_score, generate_keys, is_field_element,
+    verify_proof, BorrowerFigures, EligibilityInputs, ProofError, REPUTATION_CAP,
+};
+
+// Setup takes a couple of seconds, so the proving tests share one pair of keys
+fn keys() -> &'static (Vec<u8>, Vec<u8>) {
+    static KEYS: OnceLock<(Vec<u8>, Vec<u8>)> = OnceLock::new();
+    KEYS.get_or_init(|| generate_keys(&mut StdRng::seed_from_u64(0)).unwrap())
+}
+
+fn inputs() -> EligibilityInputs {
+    EligibilityInputs {
+        borrower: BorrowerFigures { emissions: 5, returned: 3, borrowed: 4, debt: 20, credits: 10, reputation: 2 },
+        salt: [7; 32],
+        lender_credits: 1000,
+        lender_debt: 30,
+        threshold: 2000,
+    }
+}
+
+fn synthesize(inputs: EligibilityInputs) -> ConstraintSystemRef<Fr> {
+    let cs = ConstraintSystem::new_ref();
+    EligibilityCircuit { inputs }.generate_constraints(cs.clone()).unwrap();
+    cs
+}
+
+#[test]
+fn field_encoding_round_trips() {
+    let value = Fr::from(123_456_789u64);
+    let bytes = field::encode(&value);
+    assert_eq!(bytes.len(), field::ENCODED_LENGTH);
+    assert_eq!(&bytes[..4], &123_456_789u32.to_le_bytes());
+    assert_eq!(field::decode(&bytes), Ok(value));
+    assert_eq!(field::decode_u64(&bytes), Ok(123_456_789));
+    assert_eq!(field::decode_u64(&field::encode(&Fr::from(u64::MAX))), Ok(u64::MAX));
+    assert_eq!(field::decode_u64(&field::encode(&(Fr::from(u64::MAX) + Fr::from(1u8)))), Err(ProofError::Serialization));
+
+    assert_eq!(field::decode(&bytes[..31]), Err(ProofError::Serialization));
+    // Above the modulus, so not the canonical encoding of anything
+    assert_eq!(field::decode(&[0xff; 32]), Err(ProofError::Serialization));
+    assert!(!is_field_element(&[0xff; 32]));
+
+    assert_eq!(field::from_signed(-5) + Fr::from(5u8), Fr::from(0u8));
+    assert_eq!(field::from_signed(5), Fr::from(5u8));
+}
+
+#[test]
+fn commitments_bind_figures_and_salt() {
+    let figures = inputs().borrower;
+    let commitment = figures.commitment(&[7; 32]);
+    assert!(is_field_element(&commitment));
+    assert_eq!(commitment, figures.commitment(&[7; 32]));
+    assert_ne!(commitment, figures.commitment(&[8; 32]));
+    assert_ne!(commitment, BorrowerFigures { debt: 0, ..figures }.commitment(&[7; 32]));
+}
+
+#[test]
+fn builder_enforces_products() {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let mut builder = CircuitBuilder::new(cs.clone());
+    let a = builder.public_input(Fr::from(3u8)).unwrap();
+    let b = builder.witness(Fr::from(4u8)).unwrap();
+    let product = builder.mul(&a, &b).unwrap();
+    let sum = builder.linear_combination(&[(Fr::from(2u8), &product), (-Fr::from(1u8), &a)]);
+    assert_eq!(sum.value(), Fr::from(21u8));
+    let expected = builder.constant(Fr::from(21u8));
+    builder.assert_equal(&sum, &expected).unwrap();
+    builder.assert_boolean(&builder.constant(Fr::from(1u8))).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    let wrong = builder.constant(Fr::from(22u8));
+    builder.assert_equal(&sum, &wrong).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let mut builder = CircuitBuilder::new(cs.clone());
+    let two = builder.witness(Fr::from(2u8)).unwrap();
+    builder.assert_boolean(&two).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+// Runs `gadget` on fresh witnesses for `a` and `b`, returning its output and whether the circuit holds
+fn compare(a: i64, b: i64, gadget: fn(&mut CircuitBuilder, &Wire, &Wire) -> Wire) -> (Fr, bool) {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let mut builder = CircuitBuilder::new(cs.clone());
+    let a = builder.witness(field::from_signed(a as i128)).unwrap();
+    let b = builder.witness(field::from_signed(b as i128)).unwrap();
+    let output = gadget(&mut builder, &a, &b);
+    (output.value(), cs.is_satisfied().unwrap())
+}
+
+#[test]
+fn range_checks_bound_values() {
+    for (value, satisfied) in [(0u64, true), (255, true), (256, false)] {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut builder = CircuitBuilder::new(cs.clone());
+        let wire = builder.witness(Fr::from(value)).unwrap();
+        let bits = builder.to_bits(&wire, 8).unwrap();
+        assert_eq!(bits.len(), 8);
+        assert_eq!(cs.is_satisfied().unwrap(), satisfied, "{}", value);
+    }
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let mut builder = CircuitBuilder::new(cs.clone());
+    let wire = builder.witness(field::from_signed(-1)).unwrap();
+    builder.range_check(&wire, 32).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn comparisons_order_values() {
+    let less_than = |builder: &mut CircuitBuilder, a: &Wire, b: &Wire| builder.less_than(a, b, 8).unwrap();
+    let (one, zero) = (Fr::from(1u8), Fr::from(0u8));
+    assert_eq!(compare(3, 5, less_than), (one, true));
+    assert_eq!(compare(5, 5, less_than), (zero, true));
+    assert_eq!(compare(255, 0, less_than), (zero, true));
+    assert_eq!(compare(-200, 10, less_than), (one, true));
+    // A difference past the width has no valid decomposition
+    assert!(!compare(300, 0, less_than).1);
+
+    let min = |builder: &mut CircuitBuilder, a: &Wire, b: &Wire| builder.min(a, b, 8).unwrap();
+    let max = |builder: &mut CircuitBuilder, a: &Wire, b: &Wire| builder.max(a, b, 8).unwrap();
+    assert_eq!(compare(150, 100, min), (Fr::from(100u8), true));
+    assert_eq!(compare(7, 100, min), (Fr::from(7u8), true));
+    assert_eq!(compare(150, 100, max), (Fr::from(150u8), true));
+    assert_eq!(compare(7, 100, max), (Fr::from(100u8), true));
+}
+
+#[test]
+#[should_panic(expected = "cannot decompose into 251 bits")]
+fn decompositions_stay_below_the_modulus() {
+    let mut builder = CircuitBuilder::new(ConstraintSystem::<Fr>::new_ref());
+    let wire = builder.constant(Fr::from(1u8));
+    builder.to_bits(&wire, MAX_BITS + 1).unwrap();
+}
+
+#[test]
+fn score_follows_the_formula() {
+    // 2 * (10 + 1000) - (20 + 30) - 2 * 5 + 100 * 3 - 50 * 4
+    assert_eq!(inputs().score(), 2060);
+    let borrower = BorrowerFigures { debt: 100, ..BorrowerFigures::default() };
+    assert_eq!(eligibility_score(&borrower, 0, 0), -100);
+    // Reputation stops counting at the cap
+    let borrower = BorrowerFigures { credits: 10, reputation: 150, ..BorrowerFigures::default() };
+    assert_eq!(eligibility_score(&borrower, 0, 0), 10 * i128::from(REPUTATION_CAP));
+}
+
+#[test]
+fn circuit_is_satisfied_only_at_or_above_the_threshold() {
+    assert!(synthesize(inputs()).is_satisfied().unwrap());
+    assert!(synthesize(EligibilityInputs { threshold: 2060, ..inputs() }).is_satisfied().unwrap());
+    assert!(!synthesize(EligibilityInputs { threshold: 2061, ..inputs() }).is_satisfied().unwrap());
+    // A negative score stays below even a zero threshold
+    let borrower = BorrowerFigures { debt: 100, ..BorrowerFigures::default() };
+    assert!(!synthesize(EligibilityInputs { borrower, threshold: 0, ..inputs() }).is_satisfied().unwrap());
+
+    // The cap holds inside the proof: reputation 150 proves no more than reputation 100
+    let borrower = BorrowerFigures { reputation: 150, ..inputs().borrower };
+    let capped = EligibilityInputs { borrower: BorrowerFigures { reputation: 100, ..borrower }, ..inputs() }.score();
+    assert!(synthesize(EligibilityInputs { borrower, threshold: capped as u64, ..inputs() }).is_satisfied().unwrap());
+    assert!(!synthesize(EligibilityInputs { borrower, threshold: capped as u64 + 1, ..inputs() }).is_satisfied().unwrap());
+
+    // Figures only need to fit in u64
+    let borrower = BorrowerFigures { credits: 1 << 40, reputation: 1, ..BorrowerFigures::default() };
+    let inputs = EligibilityInputs { borrower, lender_credits: 1 << 40, lender_debt: 0, threshold: 1 << 41, ..inputs() };
+    assert!(synthesize(inputs).is_satisfied().unwrap());
+}
+
+#[test]
+fn proofs_verify_against_the_commitment() {
+    let (proving_key, verifying_key) = keys();
+    let inputs = inputs();
+    let (score, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(score, 2060);
+
+    let commitment = inputs.borrower.commitment(&inputs.salt);
+    let public_inputs = eligibility_public_inputs(&commitment, 1000, 30, 2000).unwrap();
+    assert_eq!(public_inputs, inputs.public_inputs());
+    assert_eq!(verify_proof(verifying_key, &proof, &public_inputs), Ok(true));
+
+    // Any other statement fails
+    for (lender_credits, lender_debt, threshold) in [(1001, 30, 2000), (1000, 0, 2000), (1000, 30, 1999)] {
+        let public_inputs = eligibility_public_inputs(&commitment, lender_credits, lender_debt, threshold).unwrap();
+        assert_eq!(verify_proof(verifying_key, &proof, &public_inputs), Ok(false));
+    }
+    let other = inputs.borrower.commitment(&[8; 32]);
+    let public_inputs = eligibility_public_inputs(&other, 1000, 30, 2000).unwrap();
+    assert_eq!(verify_proof(verifying_key, &proof, &public_inputs), Ok(false));
+}
+
+#[test]
+fn malformed_proofs_are_rejected() {
+    let (proving_key, verifying_key) = keys();
+    let inputs = inputs();
+    let (_, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(1)).unwrap();
+    let public_inputs = inputs.public_inputs();
+
+    assert_eq!(
+        verify_proof(verifying_key, &proof, &public_inputs[..3]),
+        Err(ProofError::PublicInputs { expected: 4, actual: 3 })
+    );
+    assert_eq!(verify_proof(verifying_key, &proof[1..], &public_inputs), Err(ProofError::Serialization));
+    assert_eq!(verify_proof(&verifying_key[1..], &proof, &public_inputs), Err(ProofError::Serialization));
+    assert_eq!(eligibility_public_inputs(&[0xff; 32], 0, 0, 0), Err(ProofError::Serialization));
+
+    let res = eligibility_proof(proving_key, &EligibilityInputs { threshold: 2061, ..inputs }, &mut StdRng::seed_from_u64(1));
+    assert_eq!(res, Err(ProofError::ThresholdNotMet));
+}
+
+#[test]
+fn verifying_keys_must_fit_the_circuit() {
+    let (proving_key, verifying_key) = keys();
+    let inputs = inputs();
+    let (_, proof) = eligibility_proof(proving_key, &inputs, &mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(check_verifying_key(verifying_key), Ok(()));
+    assert_eq!(check_verifying_key(&verifying_key[1..]), Err(ProofError::Serialization));
+
+    // A key for another number of public inputs, down to none at all
+    for length in [0, 4, 6] {
+        let mut key = VerifyingKey::<Bn254>::deserialize_compressed(verifying_key.as_slice()).unwrap();
+        key.gamma_abc_g1.resize(length, key.alpha_g1);
+        let mut bytes = Vec::new();
+        key.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(check_verifying_key(&bytes), Err(ProofError::VerifyingKey));
+        assert_eq!(verify_proof(&bytes, &proof, &inputs.public_inputs()), Err(ProofError::VerifyingKey));
+    }
+}